@@ -0,0 +1,179 @@
+use std::fs;
+
+use chrono::{Days, NaiveDate};
+
+use crate::app::{
+    checklist::{TodoItem, parse_todo_items},
+    tino_file::TinoFile,
+};
+
+/// Groups of the agenda, by due date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AgendaGroup {
+    Overdue,
+    Today,
+    /// Due in the next 7 days.
+    ThisWeek,
+    Later,
+    NoDueDate,
+}
+
+impl AgendaGroup {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AgendaGroup::Overdue => "Overdue",
+            AgendaGroup::Today => "Today",
+            AgendaGroup::ThisWeek => "This week",
+            AgendaGroup::Later => "Later",
+            AgendaGroup::NoDueDate => "No due date",
+        }
+    }
+
+    pub fn new(due: Option<NaiveDate>, today: NaiveDate) -> Self {
+        let Some(due) = due else {
+            return AgendaGroup::NoDueDate;
+        };
+        if due < today {
+            AgendaGroup::Overdue
+        } else if due == today {
+            AgendaGroup::Today
+        } else if due <= today + Days::new(7) {
+            AgendaGroup::ThisWeek
+        } else {
+            AgendaGroup::Later
+        }
+    }
+}
+
+/// An open todo item and the file it comes from.
+#[derive(Debug, Clone)]
+pub struct AgendaItem {
+    pub group: AgendaGroup,
+    pub tino_file: TinoFile,
+    pub todo_item: TodoItem,
+}
+
+/// Collects the open items of every todo file, sorted by group, due date and priority.
+///
+/// Files that can't be read are skipped.
+pub fn collect_agenda(tino_files: &[TinoFile], today: NaiveDate) -> Vec<AgendaItem> {
+    let mut agenda_items = vec![];
+    for tino_file in tino_files
+        .iter()
+        .filter(|tino_file| tino_file.progress.is_some())
+    {
+        let Ok(content) = fs::read_to_string(&tino_file.path) else {
+            continue;
+        };
+        for todo_item in parse_todo_items(&content) {
+            if todo_item.checked {
+                continue;
+            }
+            agenda_items.push(AgendaItem {
+                group: AgendaGroup::new(todo_item.due, today),
+                tino_file: tino_file.clone(),
+                todo_item,
+            });
+        }
+    }
+
+    // NOTE: Items without priority go after the ones with priority.
+    agenda_items.sort_by_key(|agenda_item| {
+        (
+            agenda_item.group,
+            agenda_item.todo_item.due,
+            agenda_item.todo_item.priority.is_none(),
+            agenda_item.todo_item.priority,
+        )
+    });
+    agenda_items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{
+        checklist::{Priority, Progress},
+        utils::tests::TestDir,
+    };
+
+    fn date(date: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn agenda_groups_start_at_their_boundaries() {
+        let today = date("2026-10-18");
+
+        assert_eq!(AgendaGroup::new(None, today), AgendaGroup::NoDueDate);
+        assert_eq!(
+            AgendaGroup::new(Some(date("2026-10-17")), today),
+            AgendaGroup::Overdue
+        );
+        assert_eq!(AgendaGroup::new(Some(today), today), AgendaGroup::Today);
+        assert_eq!(
+            AgendaGroup::new(Some(date("2026-10-19")), today),
+            AgendaGroup::ThisWeek
+        );
+        assert_eq!(
+            AgendaGroup::new(Some(date("2026-10-25")), today),
+            AgendaGroup::ThisWeek
+        );
+        assert_eq!(
+            AgendaGroup::new(Some(date("2026-10-26")), today),
+            AgendaGroup::Later
+        );
+    }
+
+    fn write(test_dir: &TestDir, name: &str, content: &str) -> TinoFile {
+        let path = test_dir.0.join(name);
+        fs::write(&path, content).unwrap();
+        TinoFile {
+            path: path.display().to_string(),
+            progress: Some(Progress::new(content)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn collect_agenda_sorts_open_items_by_group_due_date_and_priority() {
+        let test_dir = TestDir::new("agenda");
+        let tino_files = [
+            write(
+                &test_dir,
+                "a.md",
+                "- [ ] later due:2026-11-01\n\
+                 - [x] done due:2026-10-01\n\
+                 - [ ] no priority due:2026-10-18\n\
+                 - [ ] low due:2026-10-18 !low\n",
+            ),
+            write(
+                &test_dir,
+                "b.md",
+                "- [ ] someday\n- [ ] high due:2026-10-18 !high\n- [ ] overdue due:2026-10-17\n",
+            ),
+            TinoFile {
+                path: test_dir.0.join("note.md").display().to_string(),
+                ..Default::default()
+            },
+        ];
+
+        let agenda_items = collect_agenda(&tino_files, date("2026-10-18"));
+
+        assert_eq!(
+            agenda_items
+                .iter()
+                .map(|agenda_item| (agenda_item.group, agenda_item.todo_item.text.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (AgendaGroup::Overdue, "overdue"),
+                (AgendaGroup::Today, "high"),
+                (AgendaGroup::Today, "low"),
+                (AgendaGroup::Today, "no priority"),
+                (AgendaGroup::Later, "later"),
+                (AgendaGroup::NoDueDate, "someday"),
+            ]
+        );
+        assert_eq!(agenda_items[1].todo_item.priority, Some(Priority::High));
+    }
+}
@@ -0,0 +1,644 @@
+use std::{fmt, sync::LazyLock};
+
+use chrono::{Days, Months, NaiveDate};
+use regex::Regex;
+use serde::Serialize;
+
+/// A `- [ ]` or `- [x]` item, the bullet can also be `*`, `+` or a number like `1.`.
+static CHECKBOX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*(?:[-*+]|\d+[.)])\s+\[([ xX])\](.*)").unwrap());
+
+/// The due date of a todo item.
+static DUE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(^|\s)due:\d{4}-\d{2}-\d{2}(\s|$)").unwrap());
+
+/// A checkbox of a todo file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Checkbox {
+    /// Line of the checkbox, starting at 0.
+    pub line_index: usize,
+    pub checked: bool,
+}
+
+/// Priority of a todo item, written as `!high`, `!medium` or `!low`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    High,
+    Medium,
+    Low,
+}
+
+impl Priority {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Priority::High => "high",
+            Priority::Medium => "medium",
+            Priority::Low => "low",
+        }
+    }
+
+    fn parse(priority: &str) -> Option<Self> {
+        match priority.to_lowercase().as_str() {
+            "high" | "h" => Some(Priority::High),
+            "medium" | "med" | "m" => Some(Priority::Medium),
+            "low" | "l" => Some(Priority::Low),
+            _ => None,
+        }
+    }
+}
+
+/// Unit of the interval of a [`Recurrence`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecurrenceUnit {
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+/// How often a todo item repeats, written as `rec:1w`, `rec:3d`, `rec:monthly`...
+///
+/// The next due date is calculated from the day the item is checked, or from its due date
+/// when the interval starts with `+`, like `rec:+1w`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Recurrence {
+    pub count: u32,
+    pub unit: RecurrenceUnit,
+    pub from_due: bool,
+}
+
+impl Recurrence {
+    fn parse(recurrence: &str) -> Option<Self> {
+        let (from_due, interval) = match recurrence.strip_prefix('+') {
+            Some(interval) => (true, interval),
+            None => (false, recurrence),
+        };
+        let (count, unit) = match interval.to_lowercase().as_str() {
+            "daily" => (1, RecurrenceUnit::Day),
+            "weekly" => (1, RecurrenceUnit::Week),
+            "monthly" => (1, RecurrenceUnit::Month),
+            "yearly" => (1, RecurrenceUnit::Year),
+            interval => {
+                let unit = match interval.chars().last()? {
+                    'd' => RecurrenceUnit::Day,
+                    'w' => RecurrenceUnit::Week,
+                    'm' => RecurrenceUnit::Month,
+                    'y' => RecurrenceUnit::Year,
+                    _ => return None,
+                };
+                let count = interval[..interval.len() - 1].parse().ok()?;
+                (count, unit)
+            }
+        };
+        if count == 0 {
+            return None;
+        }
+        Some(Self {
+            count,
+            unit,
+            from_due,
+        })
+    }
+
+    /// Due date of the next copy of an item with `due` date, checked on `completed_on`.
+    ///
+    /// Items without due date, or that don't repeat from the due date, repeat from
+    /// `completed_on`. Returns `None` if the date would be out of range.
+    pub fn next_due(&self, due: Option<NaiveDate>, completed_on: NaiveDate) -> Option<NaiveDate> {
+        let start = match due {
+            Some(due) if self.from_due => due,
+            _ => completed_on,
+        };
+        match self.unit {
+            RecurrenceUnit::Day => start.checked_add_days(Days::new(self.count.into())),
+            RecurrenceUnit::Week => {
+                start.checked_add_days(Days::new(u64::from(self.count).checked_mul(7)?))
+            }
+            RecurrenceUnit::Month => start.checked_add_months(Months::new(self.count)),
+            RecurrenceUnit::Year => {
+                start.checked_add_months(Months::new(self.count.checked_mul(12)?))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let unit = match self.unit {
+            RecurrenceUnit::Day => 'd',
+            RecurrenceUnit::Week => 'w',
+            RecurrenceUnit::Month => 'm',
+            RecurrenceUnit::Year => 'y',
+        };
+        let from_due = if self.from_due { "+" } else { "" };
+        write!(f, "rec:{}{}{}", from_due, self.count, unit)
+    }
+}
+
+/// A checkbox with its text and the metadata written in it, like
+/// `- [ ] Send the report due:2026-10-20 !high @work`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TodoItem {
+    /// Line of the item, starting at 0.
+    pub line_index: usize,
+    pub checked: bool,
+    /// Text after the checkbox, without the metadata.
+    pub text: String,
+    pub due: Option<NaiveDate>,
+    pub priority: Option<Priority>,
+    /// Contexts without the `@`.
+    pub contexts: Vec<String>,
+    pub recurrence: Option<Recurrence>,
+}
+
+impl TodoItem {
+    fn new(line_index: usize, checked: bool, line_text: &str) -> Self {
+        let mut todo_item = Self {
+            line_index,
+            checked,
+            text: String::new(),
+            due: None,
+            priority: None,
+            contexts: vec![],
+            recurrence: None,
+        };
+
+        let mut words = vec![];
+        for word in line_text.split_whitespace() {
+            if let Some(due) = word.strip_prefix("due:")
+                && let Ok(due) = NaiveDate::parse_from_str(due, "%Y-%m-%d")
+            {
+                todo_item.due = Some(due);
+            } else if let Some(priority) = word.strip_prefix('!').and_then(Priority::parse) {
+                todo_item.priority = Some(priority);
+            } else if let Some(recurrence) = word.strip_prefix("rec:").and_then(Recurrence::parse) {
+                todo_item.recurrence = Some(recurrence);
+            } else if let Some(context) = word.strip_prefix('@')
+                && !context.is_empty()
+            {
+                todo_item.contexts.push(context.to_string());
+            } else {
+                words.push(word);
+            }
+        }
+        todo_item.text = words.join(" ");
+        todo_item
+    }
+}
+
+/// Finds the todo items of markdown `content`, ignoring the ones in fenced code blocks.
+pub fn parse_todo_items(content: &str) -> Vec<TodoItem> {
+    let lines: Vec<&str> = content.lines().collect();
+    parse_checkboxes(content)
+        .into_iter()
+        .map(|checkbox| {
+            let line_text = CHECKBOX
+                .captures(lines[checkbox.line_index])
+                .and_then(|captures| captures.get(2))
+                .map_or("", |line_text| line_text.as_str());
+            TodoItem::new(checkbox.line_index, checkbox.checked, line_text)
+        })
+        .collect()
+}
+
+/// Finds the checkboxes of markdown `content`, ignoring the ones in fenced code blocks.
+pub fn parse_checkboxes(content: &str) -> Vec<Checkbox> {
+    let mut checkboxes = vec![];
+    let mut in_code_block = false;
+    for (line_index, line) in content.lines().enumerate() {
+        let trimmed_line = line.trim_start();
+        if trimmed_line.starts_with("```") || trimmed_line.starts_with("~~~") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+        if let Some(captures) = CHECKBOX.captures(line) {
+            checkboxes.push(Checkbox {
+                line_index,
+                checked: &captures[1] != " ",
+            });
+        }
+    }
+    checkboxes
+}
+
+/// Checks the checkbox at `line_index` of `content`, or unchecks it if it's checked.
+///
+/// Returns `None` if there isn't a checkbox in that line. The rest of the content, line endings
+/// included, is kept as it is.
+pub fn toggle_checkbox(content: &str, line_index: usize) -> Option<String> {
+    if !parse_checkboxes(content)
+        .iter()
+        .any(|checkbox| checkbox.line_index == line_index)
+    {
+        return None;
+    }
+
+    let mut lines: Vec<String> = content.split_inclusive('\n').map(String::from).collect();
+    let line = lines.get_mut(line_index)?;
+    let mark = CHECKBOX.captures(line)?.get(1)?.range();
+    let toggled_mark = if &line[mark.clone()] == " " { "x" } else { " " };
+    line.replace_range(mark, toggled_mark);
+    Some(lines.concat())
+}
+
+/// Adds an unchecked copy of the recurring item at `line_index` of `content` below it,
+/// with the next due date. `completed_on` is the day the item was checked.
+///
+/// Returns `None` if there isn't a checked recurring item in that line, or if an open item
+/// with the same text is already due on the next due date, like when the item is checked
+/// again after unchecking it.
+pub fn add_next_recurrence(
+    content: &str,
+    line_index: usize,
+    completed_on: NaiveDate,
+) -> Option<(String, NaiveDate)> {
+    let todo_items = parse_todo_items(content);
+    let todo_item = todo_items
+        .iter()
+        .find(|todo_item| todo_item.line_index == line_index)?;
+    if !todo_item.checked {
+        return None;
+    }
+    let next_due = todo_item
+        .recurrence?
+        .next_due(todo_item.due, completed_on)?;
+    if todo_items.iter().any(|other_item| {
+        !other_item.checked && other_item.text == todo_item.text && other_item.due == Some(next_due)
+    }) {
+        return None;
+    }
+
+    let mut lines: Vec<String> = content.split_inclusive('\n').map(String::from).collect();
+    let line = lines.get_mut(line_index)?;
+    let line_ending = if line.ends_with("\r\n") { "\r\n" } else { "\n" };
+    let line_text = line.trim_end_matches(['\n', '\r']).to_string();
+    let mut next_line_text = line_text.clone();
+    let mark = CHECKBOX.captures(&line_text)?.get(1)?.range();
+    next_line_text.replace_range(mark, " ");
+    let next_due_text = format!("due:{}", next_due.format("%Y-%m-%d"));
+    next_line_text = if DUE.is_match(&next_line_text) {
+        DUE.replace(&next_line_text, format!("${{1}}{}${{2}}", next_due_text))
+            .into_owned()
+    } else {
+        format!("{} {}", next_line_text, next_due_text)
+    };
+
+    // NOTE: The last line can be missing its line ending, the copy goes after it.
+    if line.ends_with('\n') {
+        next_line_text.push_str(line_ending);
+    } else {
+        line.push_str(line_ending);
+    }
+    lines.insert(line_index + 1, next_line_text);
+    Some((lines.concat(), next_due))
+}
+
+/// Checked checkboxes of a todo file, out of all of them.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize)]
+pub struct Progress {
+    pub done: usize,
+    pub total: usize,
+}
+
+impl Progress {
+    pub fn new(content: &str) -> Self {
+        let checkboxes = parse_checkboxes(content);
+        Self {
+            done: checkboxes
+                .iter()
+                .filter(|checkbox| checkbox.checked)
+                .count(),
+            total: checkboxes.len(),
+        }
+    }
+
+    /// `true` if every checkbox is checked, a todo without checkboxes isn't done.
+    pub fn is_done(&self) -> bool {
+        self.total > 0 && self.done == self.total
+    }
+}
+
+impl fmt::Display for Progress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.done, self.total)
+    }
+}
+
+/// Which todo files are shown in the TINO files list.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum TodoFilter {
+    #[default]
+    All,
+    /// Todo files with unchecked checkboxes, or without checkboxes.
+    Open,
+    Done,
+}
+
+impl TodoFilter {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TodoFilter::All => "all",
+            TodoFilter::Open => "open todos",
+            TodoFilter::Done => "done todos",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            TodoFilter::All => TodoFilter::Open,
+            TodoFilter::Open => TodoFilter::Done,
+            TodoFilter::Done => TodoFilter::All,
+        }
+    }
+
+    /// `progress` is `None` for files that aren't todos, they are only shown with `All`.
+    pub fn matches(&self, progress: Option<Progress>) -> bool {
+        match (self, progress) {
+            (TodoFilter::All, _) => true,
+            (TodoFilter::Open, Some(progress)) => !progress.is_done(),
+            (TodoFilter::Done, Some(progress)) => progress.is_done(),
+            (_, None) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toggle_checkbox_checks_and_unchecks() {
+        let content = "# Todo\n- [ ] one\n* [x] two\n1. [X] three\n";
+
+        assert_eq!(
+            toggle_checkbox(content, 1).unwrap(),
+            "# Todo\n- [x] one\n* [x] two\n1. [X] three\n"
+        );
+        assert_eq!(
+            toggle_checkbox(content, 2).unwrap(),
+            "# Todo\n- [ ] one\n* [ ] two\n1. [X] three\n"
+        );
+        assert_eq!(
+            toggle_checkbox(content, 3).unwrap(),
+            "# Todo\n- [ ] one\n* [x] two\n1. [ ] three\n"
+        );
+    }
+
+    #[test]
+    fn toggle_checkbox_keeps_crlf_line_endings() {
+        let content = "- [ ] one\r\n- [ ] two\r\n";
+
+        assert_eq!(
+            toggle_checkbox(content, 1).unwrap(),
+            "- [ ] one\r\n- [x] two\r\n"
+        );
+    }
+
+    #[test]
+    fn toggle_checkbox_keeps_a_missing_final_newline() {
+        assert_eq!(
+            toggle_checkbox("- [ ] one\n- [ ] two", 1).unwrap(),
+            "- [ ] one\n- [x] two"
+        );
+    }
+
+    #[test]
+    fn toggle_checkbox_ignores_fenced_code_and_other_lines() {
+        let content = "text\n```md\n- [ ] not a todo\n```\n- [ ] todo\n";
+
+        assert_eq!(toggle_checkbox(content, 0), None);
+        assert_eq!(toggle_checkbox(content, 2), None);
+        assert_eq!(toggle_checkbox(content, 9), None);
+        assert!(toggle_checkbox(content, 4).is_some());
+    }
+
+    #[test]
+    fn parse_checkboxes_skips_fenced_code() {
+        let content = "- [x] a\n~~~\n- [ ] b\n~~~\n  - [ ] c\n";
+
+        assert_eq!(
+            parse_checkboxes(content),
+            vec![
+                Checkbox {
+                    line_index: 0,
+                    checked: true
+                },
+                Checkbox {
+                    line_index: 4,
+                    checked: false
+                },
+            ]
+        );
+    }
+
+    fn date(date: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap()
+    }
+
+    fn recurrence(recurrence: &str) -> Recurrence {
+        Recurrence::parse(recurrence).unwrap()
+    }
+
+    #[test]
+    fn recurrence_parses_intervals() {
+        assert_eq!(
+            recurrence("+2w"),
+            Recurrence {
+                count: 2,
+                unit: RecurrenceUnit::Week,
+                from_due: true
+            }
+        );
+        assert_eq!(recurrence("monthly").to_string(), "rec:1m");
+        assert_eq!(Recurrence::parse("0d"), None);
+        assert_eq!(Recurrence::parse("3x"), None);
+        assert_eq!(Recurrence::parse("d"), None);
+    }
+
+    #[test]
+    fn next_due_clamps_to_the_end_of_the_month() {
+        let completed_on = date("2026-01-31");
+
+        assert_eq!(
+            recurrence("1m").next_due(None, completed_on),
+            Some(date("2026-02-28"))
+        );
+        assert_eq!(
+            recurrence("1y").next_due(None, date("2028-02-29")),
+            Some(date("2029-02-28"))
+        );
+        assert_eq!(
+            recurrence("+1m").next_due(Some(date("2026-03-31")), completed_on),
+            Some(date("2026-04-30"))
+        );
+    }
+
+    #[test]
+    fn next_due_starts_from_the_due_date_only_with_plus() {
+        let due = Some(date("2026-10-01"));
+        let completed_on = date("2026-10-18");
+
+        assert_eq!(
+            recurrence("1w").next_due(due, completed_on),
+            Some(date("2026-10-25"))
+        );
+        assert_eq!(
+            recurrence("+1w").next_due(due, completed_on),
+            Some(date("2026-10-08"))
+        );
+        assert_eq!(
+            recurrence("+3d").next_due(None, completed_on),
+            Some(date("2026-10-21"))
+        );
+    }
+
+    #[test]
+    fn next_due_returns_none_on_overflow() {
+        let completed_on = date("2026-10-18");
+
+        assert_eq!(recurrence("400000000y").next_due(None, completed_on), None);
+        assert_eq!(recurrence("4294967295w").next_due(None, completed_on), None);
+        assert_eq!(recurrence("4294967295m").next_due(None, completed_on), None);
+    }
+
+    #[test]
+    fn add_next_recurrence_adds_an_unchecked_copy_below() {
+        let content = "# Todo\n- [x] Water plants due:2026-10-10 rec:3d !low\n- [ ] other\n";
+
+        assert_eq!(
+            add_next_recurrence(content, 1, date("2026-10-18")),
+            Some((
+                String::from(
+                    "# Todo\n- [x] Water plants due:2026-10-10 rec:3d !low\n\
+                     - [ ] Water plants due:2026-10-21 rec:3d !low\n- [ ] other\n"
+                ),
+                date("2026-10-21")
+            ))
+        );
+    }
+
+    #[test]
+    fn add_next_recurrence_appends_a_due_date() {
+        let (content, _) =
+            add_next_recurrence("- [x] Pay rent rec:monthly\n", 0, date("2026-10-18")).unwrap();
+
+        assert_eq!(
+            content,
+            "- [x] Pay rent rec:monthly\n- [ ] Pay rent rec:monthly due:2026-11-18\n"
+        );
+    }
+
+    #[test]
+    fn add_next_recurrence_keeps_crlf_line_endings() {
+        let (content, _) =
+            add_next_recurrence("- [x] a rec:1d\r\n- [ ] b\r\n", 0, date("2026-10-18")).unwrap();
+
+        assert_eq!(
+            content,
+            "- [x] a rec:1d\r\n- [ ] a rec:1d due:2026-10-19\r\n- [ ] b\r\n"
+        );
+    }
+
+    #[test]
+    fn add_next_recurrence_handles_a_missing_final_newline() {
+        let (content, _) =
+            add_next_recurrence("- [ ] b\n- [x] a rec:1d", 1, date("2026-10-18")).unwrap();
+
+        assert_eq!(
+            content,
+            "- [ ] b\n- [x] a rec:1d\n- [ ] a rec:1d due:2026-10-19"
+        );
+    }
+
+    #[test]
+    fn add_next_recurrence_ignores_unchecked_fenced_and_plain_items() {
+        let completed_on = date("2026-10-18");
+
+        assert_eq!(
+            add_next_recurrence("- [ ] a rec:1d\n", 0, completed_on),
+            None
+        );
+        assert_eq!(add_next_recurrence("- [x] a\n", 0, completed_on), None);
+        assert_eq!(
+            add_next_recurrence("```\n- [x] a rec:1d\n```\n", 1, completed_on),
+            None
+        );
+    }
+
+    #[test]
+    fn add_next_recurrence_adds_one_copy_when_checked_again() {
+        let completed_on = date("2026-10-18");
+        let content = "- [ ] Water plants rec:3d\n";
+
+        let checked = toggle_checkbox(content, 0).unwrap();
+        let (recurred, _) = add_next_recurrence(&checked, 0, completed_on).unwrap();
+        let unchecked = toggle_checkbox(&recurred, 0).unwrap();
+        let checked_again = toggle_checkbox(&unchecked, 0).unwrap();
+
+        assert_eq!(
+            recurred,
+            "- [x] Water plants rec:3d\n- [ ] Water plants rec:3d due:2026-10-21\n"
+        );
+        assert_eq!(add_next_recurrence(&checked_again, 0, completed_on), None);
+        assert!(add_next_recurrence(&checked_again, 0, date("2026-10-19")).is_some());
+    }
+
+    #[test]
+    fn parse_todo_items_reads_the_metadata_of_each_item() {
+        let content = "- [ ] Send the report due:2026-10-20 !high @work @mail\n\
+                       - [x] Call Ana !M rec:+1w\n\
+                       - [ ] Read due:tomorrow !urgent @ email@example.com\n";
+
+        assert_eq!(
+            parse_todo_items(content),
+            vec![
+                TodoItem {
+                    line_index: 0,
+                    checked: false,
+                    text: String::from("Send the report"),
+                    due: Some(date("2026-10-20")),
+                    priority: Some(Priority::High),
+                    contexts: vec![String::from("work"), String::from("mail")],
+                    recurrence: None,
+                },
+                TodoItem {
+                    line_index: 1,
+                    checked: true,
+                    text: String::from("Call Ana"),
+                    due: None,
+                    priority: Some(Priority::Medium),
+                    contexts: vec![],
+                    recurrence: Some(recurrence("+1w")),
+                },
+                TodoItem {
+                    line_index: 2,
+                    checked: false,
+                    text: String::from("Read due:tomorrow !urgent @ email@example.com"),
+                    due: None,
+                    priority: None,
+                    contexts: vec![],
+                    recurrence: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn priorities_are_ordered_from_high_to_low() {
+        let mut priorities = vec![Priority::Low, Priority::High, Priority::Medium];
+        priorities.sort();
+
+        assert_eq!(
+            priorities,
+            vec![Priority::High, Priority::Medium, Priority::Low]
+        );
+        assert_eq!(Priority::parse("LOW"), Some(Priority::Low));
+        assert_eq!(Priority::parse("med"), Some(Priority::Medium));
+        assert_eq!(Priority::parse("urgent"), None);
+    }
+}
@@ -1,21 +1,361 @@
-use std::{fs, path::PathBuf};
+use std::{
+    borrow::Cow,
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
 use color_eyre::owo_colors::OwoColorize;
-use serde::Deserialize;
+use ratatui::style::Color;
+use serde::{Deserialize, Deserializer, de};
+use softpath::prelude::*;
+use toml_edit::{DocumentMut, Item, Table, value};
 
-use crate::app::utils::TinoError;
+use crate::app::{
+    file_name_pattern::{FileNamePattern, Placeholder, sanitize},
+    tino_file::{SortMode, TIMESTAMP_FORMAT},
+    utils::TinoError,
+};
 
+/// Legacy `[tino_dirs]` table, every directory is optional.
 #[derive(Debug, Default, Clone, Deserialize)]
 pub struct TinoDirs {
-    pub todos_dir: String,
-    pub ideas_dir: String,
-    pub notes_dir: String,
-    pub academic_notes_dir: String,
+    pub todos_dir: Option<String>,
+    pub ideas_dir: Option<String>,
+    pub notes_dir: Option<String>,
+    pub academic_notes_dir: Option<String>,
+}
+
+impl TinoDirs {
+    fn into_tino_types(self) -> Vec<TinoType> {
+        [
+            ("Todos", "TODO", self.todos_dir, true),
+            ("Ideas", "IDEA", self.ideas_dir, false),
+            ("Notes", "NOTE", self.notes_dir, false),
+            (
+                "Academic notes",
+                "ACAD. NOTE",
+                self.academic_notes_dir,
+                false,
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(name, label, dir, todo)| {
+            dir.map(|dir| TinoType {
+                name: name.to_string(),
+                dir,
+                label: label.to_string(),
+                todo,
+                template: None,
+            })
+        })
+        .collect()
+    }
+}
+
+/// A user-defined TINO type, declared with `[[tino_types]]`.
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct TinoType {
+    /// Name shown in the Type list.
+    pub name: String,
+    /// Directory where files of this type live.
+    pub dir: String,
+    /// Label shown before the file name in the TINO files list, defaults to the name in uppercase.
+    #[serde(default)]
+    pub label: String,
+    /// Files of this type are todo lists, their checkboxes are counted.
+    #[serde(default)]
+    pub todo: bool,
+    /// Template of new files, relative to `templates_dir`, defaults to `<name in lowercase>.md`.
+    #[serde(default)]
+    pub template: Option<String>,
+}
+
+/// A PARA category, declared in the `items` list of the `[categories]` table.
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct Category {
+    pub name: String,
+    /// Short code appended to file names instead of the name.
+    #[serde(default)]
+    pub code: Option<String>,
+    /// Any color ratatui can parse, like `"blue"`, `"light-green"` or `"#ff8800"`.
+    #[serde(default)]
+    pub color: Option<String>,
+}
+
+impl Category {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// The "no category" option, always shown first in the PARA category list.
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    pub fn is_none(&self) -> bool {
+        self.name.is_empty()
+    }
+
+    /// Text appended to the file name, the code if it exist or the name.
+    pub fn file_name_suffix(&self) -> &str {
+        self.code.as_deref().unwrap_or(&self.name)
+    }
+
+    pub fn color(&self) -> Option<Color> {
+        self.color
+            .as_deref()
+            .and_then(|color| Color::from_str(color).ok())
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Categories {
+    #[serde(default = "Categories::default_items")]
+    pub items: Vec<Category>,
+}
+
+impl Categories {
+    fn default_items() -> Vec<Category> {
+        ["Project", "Area", "Resource", "Archive"]
+            .into_iter()
+            .map(Category::new)
+            .collect()
+    }
+}
+
+impl Default for Categories {
+    fn default() -> Self {
+        Self {
+            items: Self::default_items(),
+        }
+    }
+}
+
+/// Options of the TINO files list, saved back to the config file when they change in the TUI.
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct ListOptions {
+    #[serde(default)]
+    pub sort: SortMode,
+    /// Group files by type and category.
+    #[serde(default)]
+    pub group: bool,
+}
+
+/// Options of the file preview, `auto` is saved back to the config file when it changes in the TUI.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PreviewOptions {
+    /// Preview the selected TINO file while moving through the list.
+    #[serde(default = "PreviewOptions::default_auto")]
+    pub auto: bool,
+    /// Bytes of a file shown in the preview, bigger files are cut.
+    #[serde(default = "PreviewOptions::default_max_bytes")]
+    pub max_bytes: u64,
+}
+
+impl PreviewOptions {
+    fn default_auto() -> bool {
+        true
+    }
+
+    fn default_max_bytes() -> u64 {
+        256 * 1024
+    }
+}
+
+impl Default for PreviewOptions {
+    fn default() -> Self {
+        Self {
+            auto: Self::default_auto(),
+            max_bytes: Self::default_max_bytes(),
+        }
+    }
+}
+
+/// Time zone of new timestamps, of timestamps without offset and of the agenda's today,
+/// written as `"local"`, `"utc"` or an IANA name like `"America/New_York"`.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum TinoTimeZone {
+    /// The default. Timestamps used to be in UTC unless `[file_name] time = "local"` was set,
+    /// `timezone = "utc"` keeps them in UTC.
+    #[default]
+    Local,
+    Utc,
+    Named(Tz),
+}
+
+impl<'de> Deserialize<'de> for TinoTimeZone {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let time_zone = String::deserialize(deserializer)?;
+        match time_zone.to_lowercase().as_str() {
+            "local" => Ok(TinoTimeZone::Local),
+            "utc" => Ok(TinoTimeZone::Utc),
+            _ => time_zone.parse::<Tz>().map(TinoTimeZone::Named).map_err(|_| {
+                de::Error::custom(format!(
+                    "{} isn't a time zone, use local, utc or an IANA name like America/New_York",
+                    time_zone
+                ))
+            }),
+        }
+    }
+}
+
+impl TinoTimeZone {
+    pub fn now(&self) -> DateTime<FixedOffset> {
+        self.convert(Utc::now())
+    }
+
+    pub fn today(&self) -> NaiveDate {
+        self.now().date_naive()
+    }
+
+    /// The same instant as `date_time`, in this time zone.
+    pub fn convert<Z: TimeZone>(&self, date_time: DateTime<Z>) -> DateTime<FixedOffset> {
+        match self {
+            TinoTimeZone::Local => date_time.with_timezone(&Local).fixed_offset(),
+            TinoTimeZone::Utc => date_time.with_timezone(&Utc).fixed_offset(),
+            TinoTimeZone::Named(tz) => date_time.with_timezone(tz).fixed_offset(),
+        }
+    }
+
+    /// `date_time` as a time of this time zone.
+    ///
+    /// Returns `None` for times skipped by a daylight saving change.
+    pub fn localize(&self, date_time: NaiveDateTime) -> Option<DateTime<FixedOffset>> {
+        match self {
+            TinoTimeZone::Local => Local
+                .from_local_datetime(&date_time)
+                .earliest()
+                .map(|date_time| date_time.fixed_offset()),
+            TinoTimeZone::Utc => Some(Utc.from_utc_datetime(&date_time).fixed_offset()),
+            TinoTimeZone::Named(tz) => tz
+                .from_local_datetime(&date_time)
+                .earliest()
+                .map(|date_time| date_time.fixed_offset()),
+        }
+    }
+}
+
+/// Options of the names of new files, declared in the `[file_name]` table.
+#[derive(Debug, Clone, Deserialize)]
+pub struct FileNameOptions {
+    /// Placeholders are `{title}`, `{timestamp}`, `{category}` and `{type}`, `.md` is appended.
+    #[serde(default = "FileNameOptions::default_pattern")]
+    pub pattern: String,
+    /// A `strftime` format, like `%Y-%m-%d_%H-%M-%S`.
+    #[serde(default = "FileNameOptions::default_timestamp_format")]
+    pub timestamp_format: String,
+    /// Lowercase the title, category and type, and replace what isn't a letter or a number
+    /// with `slug_separator`.
+    #[serde(default)]
+    pub slugify: bool,
+    #[serde(default = "FileNameOptions::default_slug_separator")]
+    pub slug_separator: String,
+    /// Deprecated, `utc` or `local`. Used as the time zone when the top-level `timezone`
+    /// isn't set, see [`ConfigFile::timezone`].
+    #[serde(default)]
+    pub time: Option<TinoTimeZone>,
+    /// Built from `pattern` and `timestamp_format` when the config is read.
+    #[serde(skip)]
+    pub file_name_pattern: Option<FileNamePattern>,
+}
+
+impl FileNameOptions {
+    fn default_pattern() -> String {
+        String::from("{title} {timestamp} - {category}")
+    }
+
+    fn default_timestamp_format() -> String {
+        String::from(TIMESTAMP_FORMAT)
+    }
+
+    fn default_slug_separator() -> String {
+        String::from("-")
+    }
+
+    /// Name of a new file, `category` is the suffix of the category and can be empty like
+    /// `title`.
+    ///
+    /// Characters that can't be in file names are replaced.
+    pub fn file_name(
+        &self,
+        title: &str,
+        timestamp: DateTime<FixedOffset>,
+        category: &str,
+        tino_type: &str,
+    ) -> Result<String, TinoError> {
+        let file_name_pattern = self.pattern()?;
+        let stem = file_name_pattern.format(|placeholder| match placeholder {
+            Placeholder::Title => self.slug(&sanitize(title)),
+            Placeholder::Timestamp => timestamp.format(&self.timestamp_format).to_string(),
+            Placeholder::Category => self.slug(&sanitize(category)),
+            Placeholder::Type => self.slug(&sanitize(tino_type)),
+        });
+        Ok(format!("{}.md", stem))
+    }
+
+    /// Pattern built when the config was read, or built now for configs made in code.
+    pub fn pattern(&self) -> Result<Cow<'_, FileNamePattern>, TinoError> {
+        match &self.file_name_pattern {
+            Some(file_name_pattern) => Ok(Cow::Borrowed(file_name_pattern)),
+            None => FileNamePattern::new(&self.pattern, &self.timestamp_format).map(Cow::Owned),
+        }
+    }
+
+    /// `text` as a slug if `slugify` is on.
+    pub fn slug(&self, text: &str) -> String {
+        if !self.slugify {
+            return text.to_string();
+        }
+        text.to_lowercase()
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !str::is_empty(word))
+            .collect::<Vec<_>>()
+            .join(&self.slug_separator)
+    }
+}
+
+impl Default for FileNameOptions {
+    fn default() -> Self {
+        Self {
+            pattern: Self::default_pattern(),
+            timestamp_format: Self::default_timestamp_format(),
+            slugify: false,
+            slug_separator: Self::default_slug_separator(),
+            time: None,
+            file_name_pattern: None,
+        }
+    }
 }
 
 #[derive(Debug, Default, Clone, Deserialize)]
 pub struct ConfigFile {
+    #[serde(default)]
     pub tino_dirs: TinoDirs,
+    #[serde(default)]
+    pub tino_types: Vec<TinoType>,
+    #[serde(default)]
+    pub categories: Categories,
+    #[serde(default)]
+    pub list: ListOptions,
+    #[serde(default)]
+    pub preview: PreviewOptions,
+    #[serde(default)]
+    pub file_name: FileNameOptions,
+    /// Read with [`ConfigFile::timezone`], older configs set it with `[file_name] time`.
+    #[serde(default)]
+    pub timezone: Option<TinoTimeZone>,
+    /// Directory of the templates of new files.
+    #[serde(default)]
+    pub templates_dir: Option<String>,
+    /// Path of the file the config was read from.
+    #[serde(skip)]
+    pub path: String,
 }
 
 impl ConfigFile {
@@ -45,12 +385,15 @@ impl ConfigFile {
             }
         }
 
-        let config_file_result = fs::read_to_string(config_file_path);
+        let config_file_result = fs::read_to_string(&config_file_path);
 
         match config_file_result {
-            Ok(config_file_content) => match toml::from_str(&config_file_content) {
+            Ok(config_file_content) => match toml::from_str::<Self>(&config_file_content) {
                 Err(error) => Err(TinoError::DeserializeConfigFileContentFailed(error)),
-                Ok(parsered_config_file) => Ok(parsered_config_file),
+                Ok(mut parsered_config_file) => {
+                    parsered_config_file.path = config_file_path;
+                    parsered_config_file.normalize()
+                }
             },
             Err(error) => {
                 eprintln!(
@@ -64,6 +407,146 @@ impl ConfigFile {
         }
     }
 
+    /// Merges the legacy `[tino_dirs]` table into `tino_types` and fills default labels.
+    fn normalize(mut self) -> Result<Self, TinoError> {
+        let legacy_tino_types = std::mem::take(&mut self.tino_dirs).into_tino_types();
+        self.tino_types.extend(legacy_tino_types);
+
+        if self.tino_types.is_empty() {
+            return Err(TinoError::NoTinoTypesConfigured);
+        }
+
+        for tino_type in self.tino_types.iter_mut() {
+            if tino_type.label.is_empty() {
+                tino_type.label = tino_type.name.to_uppercase();
+            }
+        }
+
+        self.file_name.file_name_pattern = Some(FileNamePattern::new(
+            &self.file_name.pattern,
+            &self.file_name.timestamp_format,
+        )?);
+
+        for category in self.categories.items.iter() {
+            if category.is_none() {
+                return Err(TinoError::EmptyCategoryName);
+            }
+            if category.color.is_some() && category.color().is_none() {
+                return Err(TinoError::InvalidCategoryColor(category.name.clone()));
+            }
+        }
+
+        Ok(self)
+    }
+
+    /// The configured time zone, or the deprecated `[file_name] time`, local by default.
+    pub fn timezone(&self) -> TinoTimeZone {
+        self.timezone.or(self.file_name.time).unwrap_or_default()
+    }
+
+    /// Writes the `[list]` table to the config file, keeping the rest of it as it is.
+    pub fn save_list_options(&self) -> Result<(), TinoError> {
+        self.edit_table("list", |table| {
+            table["sort"] = value(self.list.sort.as_str());
+            table["group"] = value(self.list.group);
+        })
+    }
+
+    /// Writes the `auto` option of the `[preview]` table, keeping the rest of the file as it is.
+    pub fn save_preview_options(&self) -> Result<(), TinoError> {
+        self.edit_table("preview", |table| {
+            table["auto"] = value(self.preview.auto);
+        })
+    }
+
+    /// Edits a table of the config file, creating it if it doesn't exist.
+    fn edit_table(&self, name: &str, edit: impl FnOnce(&mut Item)) -> Result<(), TinoError> {
+        let config_file_content =
+            fs::read_to_string(&self.path).map_err(TinoError::ReadConfigFileFailed)?;
+        let mut document = config_file_content
+            .parse::<DocumentMut>()
+            .map_err(TinoError::ParseConfigFileFailed)?;
+
+        if !document.contains_table(name) {
+            document[name] = Item::Table(Table::new());
+        }
+        edit(&mut document[name]);
+
+        fs::write(&self.path, document.to_string()).map_err(TinoError::WriteConfigFileFailed)
+    }
+
+    /// Reads the template of new files of `tino_type`.
+    ///
+    /// Returns `None` if the type doesn't set a template and there isn't a default one,
+    /// a template set with `template` must exist.
+    pub fn read_template(&self, tino_type: &TinoType) -> Result<Option<String>, TinoError> {
+        let expand_path = |path: &str| {
+            path.into_path()
+                .map_err(|error| TinoError::InvalidTemplatePath(path.to_string(), error))
+        };
+        let templates_dir = self.templates_dir.as_deref().map(expand_path).transpose()?;
+
+        let template_path = match (tino_type.template.as_deref(), templates_dir) {
+            (Some(template), Some(templates_dir)) => templates_dir.join(expand_path(template)?),
+            (Some(template), None) => expand_path(template)?,
+            (None, Some(templates_dir)) => {
+                let template_path =
+                    templates_dir.join(format!("{}.md", tino_type.name.to_lowercase()));
+                if !Path::exists(&template_path) {
+                    return Ok(None);
+                }
+                template_path
+            }
+            (None, None) => return Ok(None),
+        };
+
+        fs::read_to_string(&template_path)
+            .map(Some)
+            .map_err(|error| TinoError::ReadTemplateFailed(template_path, error))
+    }
+
+    /// Finds a TINO type by its name or label, ignoring case.
+    pub fn find_tino_type(&self, name: &str) -> Result<&TinoType, TinoError> {
+        self.tino_types
+            .iter()
+            .find(|tino_type| {
+                tino_type.name.eq_ignore_ascii_case(name)
+                    || tino_type.label.eq_ignore_ascii_case(name)
+            })
+            .ok_or_else(|| TinoError::TinoTypeNotFound(name.to_string()))
+    }
+
+    /// Finds a category by its name or code, ignoring case.
+    pub fn find_category(&self, name: &str) -> Result<&Category, TinoError> {
+        self.categories
+            .items
+            .iter()
+            .find(|category| {
+                category.name.eq_ignore_ascii_case(name)
+                    || category
+                        .code
+                        .as_deref()
+                        .is_some_and(|code| code.eq_ignore_ascii_case(name))
+            })
+            .ok_or_else(|| TinoError::CategoryNotFound(name.to_string()))
+    }
+
+    /// Position of a category in the config, unknown categories and no category go last.
+    pub fn category_position(&self, category: Option<&str>) -> (usize, String) {
+        match category {
+            Some(category) => match self
+                .categories
+                .items
+                .iter()
+                .position(|configured_category| configured_category.name == category)
+            {
+                Some(position) => (position, String::new()),
+                None => (self.categories.items.len(), category.to_lowercase()),
+            },
+            None => (usize::MAX, String::new()),
+        }
+    }
+
     fn get_config_file_path(config_dir_path: Option<PathBuf>) -> Result<String, TinoError> {
         match config_dir_path {
             Some(config_dir_path) => Ok(format!("{}/.tino.toml", config_dir_path.display())),
@@ -71,3 +554,127 @@ impl ConfigFile {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde::de::{IntoDeserializer, value};
+
+    use super::*;
+    use crate::app::utils::tests::TestDir;
+
+    fn time_zone(time_zone: &str) -> Result<TinoTimeZone, value::Error> {
+        TinoTimeZone::deserialize(time_zone.into_deserializer())
+    }
+
+    fn date_time(date_time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(date_time, "%Y-%m-%dT%H:%M:%S").unwrap()
+    }
+
+    #[test]
+    fn time_zones_are_local_utc_or_iana_names() {
+        assert_eq!(time_zone("local").unwrap(), TinoTimeZone::Local);
+        assert_eq!(time_zone("UTC").unwrap(), TinoTimeZone::Utc);
+        assert_eq!(
+            time_zone("America/New_York").unwrap(),
+            TinoTimeZone::Named(Tz::America__New_York)
+        );
+        assert!(time_zone("Europe/Atlantis").is_err());
+        assert!(time_zone("").is_err());
+    }
+
+    #[test]
+    fn localize_skips_daylight_saving_gaps() {
+        let madrid = TinoTimeZone::Named(Tz::Europe__Madrid);
+
+        assert_eq!(madrid.localize(date_time("2026-03-29T02:30:00")), None);
+        assert_eq!(
+            madrid.localize(date_time("2026-10-25T02:30:00")),
+            DateTime::parse_from_rfc3339("2026-10-25T02:30:00+02:00").ok()
+        );
+        assert_eq!(
+            TinoTimeZone::Utc.localize(date_time("2026-03-29T02:30:00")),
+            DateTime::parse_from_rfc3339("2026-03-29T02:30:00+00:00").ok()
+        );
+    }
+
+    #[test]
+    fn timezone_falls_back_to_the_file_name_time() {
+        let mut config_file = ConfigFile::default();
+        assert_eq!(config_file.timezone(), TinoTimeZone::Local);
+
+        config_file.file_name.time = Some(TinoTimeZone::Utc);
+        assert_eq!(config_file.timezone(), TinoTimeZone::Utc);
+
+        config_file.timezone = Some(TinoTimeZone::Named(Tz::Europe__Madrid));
+        assert_eq!(
+            config_file.timezone(),
+            TinoTimeZone::Named(Tz::Europe__Madrid)
+        );
+    }
+
+    fn tino_type(name: &str, template: Option<&str>) -> TinoType {
+        TinoType {
+            name: String::from(name),
+            template: template.map(String::from),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn read_template_reads_the_default_or_the_configured_template() {
+        let test_dir = TestDir::new("read-template");
+        fs::write(test_dir.0.join("todos.md"), "- [ ] {{title}}\n").unwrap();
+        fs::write(test_dir.0.join("cornell.md"), "# {{title}}\n").unwrap();
+        let config_file = ConfigFile {
+            templates_dir: Some(test_dir.0.display().to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            config_file
+                .read_template(&tino_type("Todos", None))
+                .unwrap()
+                .as_deref(),
+            Some("- [ ] {{title}}\n")
+        );
+        assert_eq!(
+            config_file
+                .read_template(&tino_type("Academic notes", Some("cornell.md")))
+                .unwrap()
+                .as_deref(),
+            Some("# {{title}}\n")
+        );
+        assert_eq!(
+            config_file
+                .read_template(&tino_type("Ideas", None))
+                .unwrap(),
+            None
+        );
+        assert!(matches!(
+            config_file.read_template(&tino_type("Ideas", Some("missing.md"))),
+            Err(TinoError::ReadTemplateFailed(..))
+        ));
+    }
+
+    #[test]
+    fn read_template_without_templates_dir() {
+        let test_dir = TestDir::new("read-template-path");
+        let template = test_dir.0.join("cornell.md");
+        fs::write(&template, "# {{title}}\n").unwrap();
+        let config_file = ConfigFile::default();
+
+        assert_eq!(
+            config_file
+                .read_template(&tino_type("Todos", None))
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            config_file
+                .read_template(&tino_type("Notes", Some(&template.display().to_string())))
+                .unwrap()
+                .as_deref(),
+            Some("# {{title}}\n")
+        );
+    }
+}
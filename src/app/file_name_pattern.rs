@@ -0,0 +1,616 @@
+use std::sync::LazyLock;
+
+use chrono::{
+    DateTime, NaiveDate, NaiveDateTime,
+    format::{Item, StrftimeItems},
+};
+use regex::Regex;
+
+use crate::app::utils::TinoError;
+
+/// Characters that can't be in file names on Linux, macOS or Windows.
+const RESERVED_CHARACTERS: [char; 9] = ['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
+
+/// The counter `create_new_file` adds to names that are taken, like `-2`, up to `-999`.
+static FILE_NAME_COUNTER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"-(?:[2-9]|[1-9]\d{1,2})$").unwrap());
+
+fn is_reserved(c: char) -> bool {
+    RESERVED_CHARACTERS.contains(&c) || c.is_control()
+}
+
+/// Replaces the characters of `text` that can't be in a file name with `-`, and removes the dots
+/// at the start, which hide files, and at the end, which Windows drops.
+pub fn sanitize(text: &str) -> String {
+    text.chars()
+        .map(|c| if is_reserved(c) { '-' } else { c })
+        .collect::<String>()
+        .trim_matches(|c: char| c == '.' || c.is_whitespace())
+        .to_string()
+}
+
+/// A value of a file name pattern, written as `{title}`, `{timestamp}`, `{category}` or `{type}`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Placeholder {
+    Title,
+    Timestamp,
+    Category,
+    Type,
+}
+
+impl Placeholder {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "title" => Some(Placeholder::Title),
+            "timestamp" => Some(Placeholder::Timestamp),
+            "category" => Some(Placeholder::Category),
+            "type" => Some(Placeholder::Type),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Part {
+    Text(String),
+    Placeholder(Placeholder),
+}
+
+/// Pattern of the names of new TINO files, like `{title} {timestamp} - {category}`.
+///
+/// Empty values are left out with the text before them, or after them if they are the first
+/// placeholder, so `{title} {timestamp} - {category}` without category is `{title} {timestamp}`.
+#[derive(Debug, Clone)]
+pub struct FileNamePattern {
+    parts: Vec<Part>,
+    timestamp_format: String,
+    /// Regex of each combination of the optional placeholders, the ones with more placeholders
+    /// first.
+    regexes: Vec<(Regex, Vec<Placeholder>)>,
+}
+
+impl FileNamePattern {
+    /// `pattern` needs a `{timestamp}`, so names of new files don't repeat, and
+    /// `timestamp_format` has to be a valid `strftime` format.
+    pub fn new(pattern: &str, timestamp_format: &str) -> Result<Self, TinoError> {
+        let invalid_pattern = |reason: &str| {
+            TinoError::InvalidFileNamePattern(pattern.to_string(), reason.to_string())
+        };
+        // NOTE: chrono panics when it formats a timestamp with an invalid format.
+        if StrftimeItems::new(timestamp_format).any(|item| item == Item::Error) {
+            return Err(invalid_pattern(&format!(
+                "the timestamp format {} isn't a valid strftime format",
+                timestamp_format
+            )));
+        }
+
+        let mut parts = vec![];
+        let mut rest = pattern;
+        while let Some(start) = rest.find('{') {
+            if start > 0 {
+                parts.push(Part::Text(rest[..start].to_string()));
+            }
+            let end = rest[start..]
+                .find('}')
+                .ok_or_else(|| invalid_pattern("a `{` isn't closed"))?
+                + start;
+            let placeholder = Placeholder::parse(&rest[start + 1..end]).ok_or_else(|| {
+                invalid_pattern("placeholders can only be title, timestamp, category or type")
+            })?;
+            if parts.contains(&Part::Placeholder(placeholder)) {
+                return Err(invalid_pattern("a placeholder is repeated"));
+            }
+            parts.push(Part::Placeholder(placeholder));
+            rest = &rest[end + 1..];
+        }
+        if !rest.is_empty() {
+            parts.push(Part::Text(rest.to_string()));
+        }
+        if !parts.contains(&Part::Placeholder(Placeholder::Timestamp)) {
+            return Err(invalid_pattern("it needs a {timestamp}"));
+        }
+
+        // NOTE: `:` is kept, the default timestamp format and the names of older files have it.
+        let find_reserved = |text: &str| text.chars().find(|c| *c != ':' && is_reserved(*c));
+        if let Some(reserved_character) = parts.iter().find_map(|part| match part {
+            Part::Text(text) => find_reserved(text),
+            Part::Placeholder(_) => None,
+        }) {
+            return Err(invalid_pattern(&format!(
+                "it has {:?}, which can't be in file names",
+                reserved_character
+            )));
+        }
+        let sample_timestamp = DateTime::UNIX_EPOCH
+            .fixed_offset()
+            .format(timestamp_format)
+            .to_string();
+        if let Some(reserved_character) = find_reserved(&sample_timestamp) {
+            return Err(invalid_pattern(&format!(
+                "the timestamp format {} writes {:?}, which can't be in file names",
+                timestamp_format, reserved_character
+            )));
+        }
+
+        let mut file_name_pattern = Self {
+            parts,
+            timestamp_format: timestamp_format.to_string(),
+            regexes: vec![],
+        };
+        file_name_pattern.regexes = file_name_pattern.build_regexes()?;
+        Ok(file_name_pattern)
+    }
+
+    /// Builds a file name, without extension, with the values `value` returns for each
+    /// placeholder.
+    pub fn format(&self, value: impl Fn(Placeholder) -> String) -> String {
+        let values: Vec<(Placeholder, String)> = self
+            .placeholders()
+            .map(|placeholder| (placeholder, value(placeholder)))
+            .filter(|(_, value)| !value.is_empty())
+            .collect();
+        self.kept_parts(
+            &values
+                .iter()
+                .map(|(placeholder, _)| *placeholder)
+                .collect::<Vec<_>>(),
+        )
+        .into_iter()
+        .map(|part| match part {
+            Part::Text(text) => text.clone(),
+            Part::Placeholder(placeholder) => values
+                .iter()
+                .find(|(kept, _)| kept == placeholder)
+                .map(|(_, value)| value.clone())
+                .unwrap_or_default(),
+        })
+        .collect()
+    }
+
+    /// Reads the values of a file name, without extension, written with this pattern.
+    ///
+    /// A counter added because the name was taken, like `-2`, isn't part of the values,
+    /// unless the pattern ends with the title, so titles like `Sprint-12` are kept whole.
+    /// Returns `None` if the name doesn't match the pattern.
+    pub fn parse(&self, stem: &str) -> Option<Vec<(Placeholder, String)>> {
+        let parse_stripped_counter = || {
+            FILE_NAME_COUNTER
+                .find(stem)
+                .and_then(|counter| self.parse_without_counter(&stem[..counter.start()]))
+        };
+        if let Some(Part::Placeholder(Placeholder::Title)) = self.parts.last() {
+            self.parse_without_counter(stem)
+                .or_else(parse_stripped_counter)
+        } else {
+            parse_stripped_counter().or_else(|| self.parse_without_counter(stem))
+        }
+    }
+
+    fn parse_without_counter(&self, stem: &str) -> Option<Vec<(Placeholder, String)>> {
+        self.regexes.iter().find_map(|(regex, placeholders)| {
+            let captures = regex.captures(stem)?;
+            let values: Vec<(Placeholder, String)> = placeholders
+                .iter()
+                .enumerate()
+                .map(|(i, placeholder)| (*placeholder, captures[i + 1].to_string()))
+                .collect();
+            // NOTE: The regex of the timestamp is loose, chrono checks it's a real date.
+            values
+                .iter()
+                .find(|(placeholder, _)| *placeholder == Placeholder::Timestamp)
+                .and_then(|(_, timestamp)| self.parse_timestamp(timestamp))?;
+            Some(values)
+        })
+    }
+
+    /// Parses a timestamp written with the timestamp format, formats without time are midnight.
+    pub fn parse_timestamp(&self, timestamp: &str) -> Option<NaiveDateTime> {
+        NaiveDateTime::parse_from_str(timestamp, &self.timestamp_format)
+            .ok()
+            .or_else(|| {
+                NaiveDate::parse_from_str(timestamp, &self.timestamp_format)
+                    .ok()?
+                    .and_hms_opt(0, 0, 0)
+            })
+    }
+
+    fn placeholders(&self) -> impl Iterator<Item = Placeholder> {
+        self.parts.iter().filter_map(|part| match part {
+            Part::Placeholder(placeholder) => Some(*placeholder),
+            Part::Text(_) => None,
+        })
+    }
+
+    /// Parts of the pattern when only `kept` placeholders have a value.
+    ///
+    /// Between two kept placeholders goes the text right before the second one.
+    fn kept_parts(&self, kept: &[Placeholder]) -> Vec<&Part> {
+        let mut kept_parts = vec![];
+        if let Some(Part::Text(_)) = self.parts.first() {
+            kept_parts.push(&self.parts[0]);
+        }
+        let mut first = true;
+        for (i, part) in self.parts.iter().enumerate() {
+            let Part::Placeholder(placeholder) = part else {
+                continue;
+            };
+            if !kept.contains(placeholder) {
+                continue;
+            }
+            if !first
+                && i > 0
+                && let Part::Text(_) = self.parts[i - 1]
+            {
+                kept_parts.push(&self.parts[i - 1]);
+            }
+            kept_parts.push(part);
+            first = false;
+        }
+        if self.parts.len() > 1
+            && let Some(Part::Text(_)) = self.parts.last()
+        {
+            kept_parts.push(&self.parts[self.parts.len() - 1]);
+        }
+        kept_parts
+    }
+
+    fn build_regexes(&self) -> Result<Vec<(Regex, Vec<Placeholder>)>, TinoError> {
+        let optional: Vec<Placeholder> = self
+            .placeholders()
+            .filter(|placeholder| *placeholder != Placeholder::Timestamp)
+            .collect();
+
+        let mut combinations: Vec<Vec<Placeholder>> = (0..1u32 << optional.len())
+            .map(|mask| {
+                self.placeholders()
+                    .filter(
+                        |placeholder| match optional.iter().position(|o| o == placeholder) {
+                            Some(i) => mask & (1 << i) != 0,
+                            None => true,
+                        },
+                    )
+                    .collect()
+            })
+            .collect();
+        combinations.sort_by_key(|kept| std::cmp::Reverse(kept.len()));
+
+        combinations
+            .into_iter()
+            .map(|kept| {
+                let mut regex = String::from("^");
+                for part in self.kept_parts(&kept) {
+                    match part {
+                        Part::Text(text) => regex.push_str(&regex::escape(text)),
+                        // NOTE: Greedy, so titles with timestamps use the last one.
+                        Part::Placeholder(Placeholder::Timestamp) => {
+                            regex.push_str(&format!(
+                                "({})",
+                                timestamp_regex(&self.timestamp_format)
+                            ));
+                        }
+                        Part::Placeholder(_) => regex.push_str("(.+)"),
+                    }
+                }
+                regex.push('$');
+                Regex::new(&regex)
+                    .map(|regex| (regex, kept))
+                    .map_err(|error| {
+                        TinoError::InvalidFileNamePattern(
+                            self.timestamp_format.clone(),
+                            error.to_string(),
+                        )
+                    })
+            })
+            .collect()
+    }
+}
+
+/// Regex matching the text chrono writes with a `strftime` format.
+fn timestamp_regex(format: &str) -> String {
+    let mut regex = String::new();
+    let mut chars = format.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            regex.push_str(&regex::escape(&c.to_string()));
+            continue;
+        }
+        // NOTE: Padding flags, like `%-d`, and fraction widths, like `%.3f`, are skipped.
+        while chars
+            .peek()
+            .is_some_and(|c| matches!(c, '-' | '_' | '0' | '.' | '1'..='9'))
+        {
+            chars.next();
+        }
+        let specifier_regex = match chars.next() {
+            Some('Y' | 'G') => r"\d{4}",
+            Some('C' | 'y' | 'g' | 'm' | 'd' | 'H' | 'I' | 'M' | 'S' | 'U' | 'W' | 'V') => {
+                r"\d{1,2}"
+            }
+            Some('e' | 'k' | 'l') => r"\s?\d{1,2}",
+            Some('j') => r"\d{1,3}",
+            Some('u' | 'w') => r"\d",
+            Some('b' | 'h' | 'a') => r"[A-Za-z]{3}",
+            Some('B' | 'A') => r"[A-Za-z]+",
+            Some('p' | 'P') => r"[AaPp][Mm]",
+            Some('f' | 's') => r"\.?\d+",
+            Some('F') => r"\d{4}-\d{1,2}-\d{1,2}",
+            Some('T' | 'X') => r"\d{1,2}:\d{1,2}:\d{1,2}",
+            Some('R') => r"\d{1,2}:\d{1,2}",
+            Some('D' | 'x') => r"\d{1,2}/\d{1,2}/\d{1,2}",
+            Some('z') => r"[+-]\d{2}:?\d{2}",
+            Some('%') => "%",
+            _ => ".+?",
+        };
+        regex.push_str(specifier_regex);
+    }
+    regex
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEFAULT_TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+    fn pattern(pattern: &str) -> FileNamePattern {
+        FileNamePattern::new(pattern, DEFAULT_TIMESTAMP_FORMAT).unwrap()
+    }
+
+    fn values(title: &str, timestamp: &str, category: &str) -> impl Fn(Placeholder) -> String {
+        move |placeholder| {
+            match placeholder {
+                Placeholder::Title => title,
+                Placeholder::Timestamp => timestamp,
+                Placeholder::Category => category,
+                Placeholder::Type => "Todos",
+            }
+            .to_string()
+        }
+    }
+
+    fn parsed(values: &[(Placeholder, &str)]) -> Option<Vec<(Placeholder, String)>> {
+        Some(
+            values
+                .iter()
+                .map(|(placeholder, value)| (*placeholder, value.to_string()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn new_rejects_invalid_patterns() {
+        let invalid_patterns = [
+            "{title}",
+            "{title} {timestamp",
+            "{title} {timestamp} {date}",
+            "{title} {timestamp} {title}",
+        ];
+
+        for invalid_pattern in invalid_patterns {
+            assert!(
+                FileNamePattern::new(invalid_pattern, DEFAULT_TIMESTAMP_FORMAT).is_err(),
+                "{invalid_pattern}"
+            );
+        }
+    }
+
+    #[test]
+    fn new_rejects_invalid_timestamp_formats() {
+        assert!(FileNamePattern::new("{title} {timestamp}", "%Y-%Q").is_err());
+        assert!(FileNamePattern::new("{title} {timestamp}", "%Y-%").is_err());
+        assert!(FileNamePattern::new("{title} {timestamp}", "%Y%m%d-%H%M%S").is_ok());
+    }
+
+    #[test]
+    fn format_and_parse_round_trip() {
+        let file_name_pattern = pattern("{title} {timestamp} - {category}");
+        let stem = file_name_pattern.format(values("Release notes", "2026-10-18T10:00:00", "P"));
+
+        assert_eq!(stem, "Release notes 2026-10-18T10:00:00 - P");
+        assert_eq!(
+            file_name_pattern.parse(&stem),
+            parsed(&[
+                (Placeholder::Title, "Release notes"),
+                (Placeholder::Timestamp, "2026-10-18T10:00:00"),
+                (Placeholder::Category, "P"),
+            ])
+        );
+    }
+
+    #[test]
+    fn format_and_parse_leave_out_empty_values() {
+        let file_name_pattern = pattern("{title} {timestamp} - {category}");
+
+        let stem = file_name_pattern.format(values("notes", "2026-10-18T10:00:00", ""));
+        assert_eq!(stem, "notes 2026-10-18T10:00:00");
+        assert_eq!(
+            file_name_pattern.parse(&stem),
+            parsed(&[
+                (Placeholder::Title, "notes"),
+                (Placeholder::Timestamp, "2026-10-18T10:00:00"),
+            ])
+        );
+
+        let stem = file_name_pattern.format(values("", "2026-10-18T10:00:00", "Area"));
+        assert_eq!(stem, "2026-10-18T10:00:00 - Area");
+        assert_eq!(
+            file_name_pattern.parse(&stem),
+            parsed(&[
+                (Placeholder::Timestamp, "2026-10-18T10:00:00"),
+                (Placeholder::Category, "Area"),
+            ])
+        );
+    }
+
+    #[test]
+    fn format_and_parse_custom_patterns() {
+        let file_name_pattern =
+            FileNamePattern::new("{timestamp}_{title}_{category} ({type})", "%Y%m%d-%H%M%S")
+                .unwrap();
+        let stem = file_name_pattern.format(values("my-title", "20261018-094850", "p"));
+
+        assert_eq!(stem, "20261018-094850_my-title_p (Todos)");
+        assert_eq!(
+            file_name_pattern.parse(&stem),
+            parsed(&[
+                (Placeholder::Timestamp, "20261018-094850"),
+                (Placeholder::Title, "my-title"),
+                (Placeholder::Category, "p"),
+                (Placeholder::Type, "Todos"),
+            ])
+        );
+        assert_eq!(
+            file_name_pattern.parse("20261018-094850 (Todos)"),
+            parsed(&[
+                (Placeholder::Timestamp, "20261018-094850"),
+                (Placeholder::Type, "Todos"),
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_uses_the_last_timestamp_of_titles_with_timestamps() {
+        let file_name_pattern = pattern("{title} {timestamp}");
+
+        assert_eq!(
+            file_name_pattern.parse("log 2026-01-01T00:00:00 2026-10-18T10:00:00"),
+            parsed(&[
+                (Placeholder::Title, "log 2026-01-01T00:00:00"),
+                (Placeholder::Timestamp, "2026-10-18T10:00:00"),
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_rejects_names_without_a_real_timestamp() {
+        let file_name_pattern = pattern("{title} {timestamp}");
+
+        assert_eq!(file_name_pattern.parse("random"), None);
+        assert_eq!(file_name_pattern.parse("notes 2026-13-45T10:00:00"), None);
+    }
+
+    #[test]
+    fn parse_timestamp_reads_formats_without_time_as_midnight() {
+        let file_name_pattern = FileNamePattern::new("{timestamp} {title}", "%Y-%m-%d").unwrap();
+
+        assert_eq!(
+            file_name_pattern.parse_timestamp("2026-10-18"),
+            NaiveDate::from_ymd_opt(2026, 10, 18)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+        );
+    }
+
+    #[test]
+    fn timestamp_regex_matches_what_chrono_writes() {
+        let timestamp = NaiveDate::from_ymd_opt(2026, 3, 5)
+            .unwrap()
+            .and_hms_opt(7, 8, 9)
+            .unwrap();
+        let formats = [
+            DEFAULT_TIMESTAMP_FORMAT,
+            "%Y%m%d-%H%M%S",
+            "%F %R",
+            "%d %b %Y, %-I.%M %p",
+            "%A %e %B %y %T%.3f",
+            "%j-%u %%",
+        ];
+
+        for format in formats {
+            let regex = Regex::new(&format!("^{}$", timestamp_regex(format))).unwrap();
+            let formatted = timestamp.format(format).to_string();
+            assert!(regex.is_match(&formatted), "{format}: {formatted}");
+        }
+    }
+
+    #[test]
+    fn timestamp_regex_escapes_literal_text() {
+        assert_eq!(timestamp_regex("%Y.%m"), r"\d{4}\.\d{1,2}");
+    }
+
+    #[test]
+    fn sanitize_replaces_reserved_characters() {
+        assert_eq!(sanitize("a/b: c?"), "a-b- c-");
+        assert_eq!(sanitize(r#"x\y*z"<w>|v"#), "x-y-z--w--v");
+        assert_eq!(sanitize("tab\there\n"), "tab-here-");
+        assert_eq!(sanitize("Release notes"), "Release notes");
+    }
+
+    #[test]
+    fn sanitize_trims_dots_and_whitespace() {
+        assert_eq!(sanitize(".hidden."), "hidden");
+        assert_eq!(sanitize("  ..notes.. "), "notes");
+        assert_eq!(sanitize("../../etc/x"), "-..-etc-x");
+        assert_eq!(sanitize("..."), "");
+    }
+
+    #[test]
+    fn new_rejects_reserved_characters() {
+        assert!(FileNamePattern::new("{title}/{timestamp}", DEFAULT_TIMESTAMP_FORMAT).is_err());
+        assert!(FileNamePattern::new("{title} {timestamp}?", DEFAULT_TIMESTAMP_FORMAT).is_err());
+        assert!(FileNamePattern::new("t {timestamp}", "%D").is_err());
+        assert!(FileNamePattern::new("t {timestamp}", "%Y|%m").is_err());
+        assert!(FileNamePattern::new("t {timestamp}", "%H:%M").is_ok());
+    }
+
+    #[test]
+    fn parse_ignores_the_counter_of_taken_names() {
+        let file_name_pattern = pattern("{title} {timestamp} - {category}");
+
+        assert_eq!(
+            file_name_pattern.parse("hello 2026-10-18T10:00:00 - Project-2"),
+            parsed(&[
+                (Placeholder::Title, "hello"),
+                (Placeholder::Timestamp, "2026-10-18T10:00:00"),
+                (Placeholder::Category, "Project"),
+            ])
+        );
+        assert_eq!(
+            file_name_pattern.parse("hello 2026-10-18T10:00:00-13"),
+            parsed(&[
+                (Placeholder::Title, "hello"),
+                (Placeholder::Timestamp, "2026-10-18T10:00:00"),
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_keeps_counters_of_titles_at_the_end() {
+        let file_name_pattern = pattern("{timestamp} {title}");
+
+        assert_eq!(
+            file_name_pattern.parse("2026-10-18T10:00:00 Sprint-12"),
+            parsed(&[
+                (Placeholder::Timestamp, "2026-10-18T10:00:00"),
+                (Placeholder::Title, "Sprint-12"),
+            ])
+        );
+        assert_eq!(
+            file_name_pattern.parse("2026-10-18T10:00:00 Phase-2"),
+            parsed(&[
+                (Placeholder::Timestamp, "2026-10-18T10:00:00"),
+                (Placeholder::Title, "Phase-2"),
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_keeps_values_that_only_look_like_counters() {
+        let file_name_pattern = FileNamePattern::new("{title}_{timestamp}", "%Y%m%d-%H%M").unwrap();
+
+        assert_eq!(
+            file_name_pattern.parse("notes_20261018-0948"),
+            parsed(&[
+                (Placeholder::Title, "notes"),
+                (Placeholder::Timestamp, "20261018-0948"),
+            ])
+        );
+        assert_eq!(
+            file_name_pattern.parse("notes_20261018-0948-2"),
+            parsed(&[
+                (Placeholder::Title, "notes"),
+                (Placeholder::Timestamp, "20261018-0948"),
+            ])
+        );
+    }
+}
@@ -0,0 +1,230 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+};
+
+use chrono::{DateTime, FixedOffset, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use crate::app::{config_file::TinoTimeZone, tino_file::TIMESTAMP_FORMAT, utils::TinoError};
+
+/// How many lines are read looking for the end of a frontmatter block.
+const MAX_FRONTMATTER_LINES: usize = 100;
+
+/// Metadata block at the start of a TINO file, YAML between `---` lines or TOML between
+/// `+++` lines.
+///
+/// Its values take priority over the ones parsed from the file name.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Frontmatter {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Name of the TINO type, only informative, the type is the one of the directory.
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub tino_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    /// Creation time, like `2026-10-18T21:05:00+02:00`, times without offset are in the
+    /// configured time zone.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created: Option<String>,
+    pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+}
+
+impl Frontmatter {
+    /// Reads the frontmatter at the start of `file`, `None` if it doesn't have one.
+    pub fn read(file: &str) -> Result<Option<Self>, TinoError> {
+        let file = File::open(file).map_err(TinoError::ReadTinoFileFailed)?;
+        let mut lines = BufReader::new(file).lines();
+
+        let Some(delimiter) = lines
+            .next()
+            .transpose()
+            .map_err(TinoError::ReadTinoFileFailed)?
+            .and_then(|first_line| Self::delimiter(&first_line))
+        else {
+            return Ok(None);
+        };
+
+        let mut block = String::new();
+        for line in lines.take(MAX_FRONTMATTER_LINES) {
+            let line = line.map_err(TinoError::ReadTinoFileFailed)?;
+            if line.trim_end() == delimiter {
+                return Self::parse_block(delimiter, &block).map(Some);
+            }
+            block.push_str(&line);
+            block.push('\n');
+        }
+        Ok(None)
+    }
+
+    /// `true` if `content` starts with a frontmatter delimiter.
+    pub fn starts(content: &str) -> bool {
+        content.lines().next().and_then(Self::delimiter).is_some()
+    }
+
+    /// YAML frontmatter for a new file, followed by a blank line.
+    pub fn to_markdown(&self) -> Result<String, TinoError> {
+        let yaml = serde_yaml_ng::to_string(self).map_err(TinoError::SerializeFrontmatterFailed)?;
+        Ok(format!("---\n{}---\n\n", yaml))
+    }
+
+    /// Creation time, `None` if it's missing or isn't a valid timestamp.
+    pub fn created(&self, timezone: TinoTimeZone) -> Option<DateTime<FixedOffset>> {
+        let created = self.created.as_deref()?.trim();
+        DateTime::parse_from_rfc3339(created).ok().or_else(|| {
+            timezone.localize(NaiveDateTime::parse_from_str(created, TIMESTAMP_FORMAT).ok()?)
+        })
+    }
+
+    fn delimiter(first_line: &str) -> Option<&'static str> {
+        match first_line.trim_end() {
+            "---" => Some("---"),
+            "+++" => Some("+++"),
+            _ => None,
+        }
+    }
+
+    fn parse_block(delimiter: &str, block: &str) -> Result<Self, TinoError> {
+        if block.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        if delimiter == "---" {
+            return serde_yaml_ng::from_str(block)
+                .map_err(|error| TinoError::ParseFrontmatterFailed(error.to_string()));
+        }
+
+        let mut table: toml::Table = toml::from_str(block)
+            .map_err(|error| TinoError::ParseFrontmatterFailed(error.to_string()))?;
+        // NOTE: TOML has its own datetimes, `created` is read as text like in YAML.
+        if let Some(toml::Value::Datetime(created)) = table.get("created") {
+            let created = created.to_string();
+            table.insert(String::from("created"), toml::Value::String(created));
+        }
+        table
+            .try_into()
+            .map_err(|error: toml::de::Error| TinoError::ParseFrontmatterFailed(error.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::utils::tests::TestDir;
+
+    fn read(test_dir: &TestDir, content: &str) -> Result<Option<Frontmatter>, TinoError> {
+        let file = test_dir.0.join("note.md");
+        std::fs::write(&file, content).unwrap();
+        Frontmatter::read(&file.display().to_string())
+    }
+
+    fn created(created: &str) -> Frontmatter {
+        Frontmatter {
+            created: Some(String::from(created)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn read_parses_yaml_and_toml_blocks() {
+        let test_dir = TestDir::new("frontmatter-read");
+
+        assert_eq!(
+            read(
+                &test_dir,
+                "---\ntitle: 'Retro: sprint'\ntype: notes\ntags: [a, b]\nstatus: draft\n---\n# Retro\n"
+            )
+            .unwrap(),
+            Some(Frontmatter {
+                title: Some(String::from("Retro: sprint")),
+                tino_type: Some(String::from("notes")),
+                category: None,
+                created: None,
+                tags: vec![String::from("a"), String::from("b")],
+                status: Some(String::from("draft")),
+            })
+        );
+        assert_eq!(
+            read(
+                &test_dir,
+                "+++\r\ntitle = \"Plan\"\r\ncategory = \"Area\"\r\n+++\r\nbody\r\n"
+            )
+            .unwrap(),
+            Some(Frontmatter {
+                title: Some(String::from("Plan")),
+                category: Some(String::from("Area")),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            read(&test_dir, "---\n---\n").unwrap(),
+            Some(Frontmatter::default())
+        );
+        assert_eq!(read(&test_dir, "# Title\n---\n").unwrap(), None);
+        assert_eq!(read(&test_dir, "---\ntitle: never closed\n").unwrap(), None);
+    }
+
+    #[test]
+    fn read_reports_malformed_blocks() {
+        let test_dir = TestDir::new("frontmatter-malformed");
+
+        assert!(matches!(
+            read(&test_dir, "---\ntitle: Retro: sprint\n---\n"),
+            Err(TinoError::ParseFrontmatterFailed(_))
+        ));
+        assert!(matches!(
+            read(&test_dir, "+++\ntitle = Plan\n+++\n"),
+            Err(TinoError::ParseFrontmatterFailed(_))
+        ));
+    }
+
+    #[test]
+    fn read_stops_looking_for_the_end_after_100_lines() {
+        let test_dir = TestDir::new("frontmatter-cutoff");
+        let block = |lines: usize| format!("---\n{}---\n", "# comment\n".repeat(lines));
+
+        assert_eq!(
+            read(&test_dir, &block(MAX_FRONTMATTER_LINES - 1)).unwrap(),
+            Some(Frontmatter::default())
+        );
+        assert_eq!(
+            read(&test_dir, &block(MAX_FRONTMATTER_LINES)).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn toml_datetimes_are_read_as_created() {
+        assert_eq!(
+            Frontmatter::parse_block("+++", "created = 2026-10-18T10:00:00+02:00\n").unwrap(),
+            created("2026-10-18T10:00:00+02:00")
+        );
+        assert_eq!(
+            Frontmatter::parse_block("+++", "created = 2026-10-18T10:00:00\n").unwrap(),
+            created("2026-10-18T10:00:00")
+        );
+    }
+
+    #[test]
+    fn created_uses_the_offset_or_the_time_zone() {
+        let madrid = TinoTimeZone::Named(chrono_tz::Europe::Madrid);
+
+        assert_eq!(
+            created("2026-10-18T10:00:00-05:00").created(madrid),
+            DateTime::parse_from_rfc3339("2026-10-18T10:00:00-05:00").ok()
+        );
+        assert_eq!(
+            created("2026-10-18T10:00:00").created(madrid),
+            DateTime::parse_from_rfc3339("2026-10-18T10:00:00+02:00").ok()
+        );
+        assert_eq!(
+            created("2026-12-18T10:00:00").created(TinoTimeZone::Utc),
+            DateTime::parse_from_rfc3339("2026-12-18T10:00:00+00:00").ok()
+        );
+        assert_eq!(created("yesterday").created(madrid), None);
+        assert_eq!(Frontmatter::default().created(madrid), None);
+    }
+}
@@ -1,2 +1,10 @@
+pub mod agenda;
+pub mod checklist;
 pub mod config_file;
+pub mod file_name_pattern;
+pub mod frontmatter;
+pub mod search;
+pub mod search_index;
+pub mod template;
+pub mod tino_file;
 pub mod utils;
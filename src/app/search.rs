@@ -0,0 +1,122 @@
+use std::fs;
+
+use regex::{Regex, RegexBuilder};
+
+use crate::app::{search_index::SearchIndex, tino_file::TinoFile, utils::TinoError};
+
+/// How the search query is matched against the lines of TINO files.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum SearchMode {
+    #[default]
+    Plain,
+    IgnoreCase,
+    Regex,
+}
+
+impl SearchMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SearchMode::Plain => "plain",
+            SearchMode::IgnoreCase => "ignore case",
+            SearchMode::Regex => "regex",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            SearchMode::Plain => SearchMode::IgnoreCase,
+            SearchMode::IgnoreCase => SearchMode::Regex,
+            SearchMode::Regex => SearchMode::Plain,
+        }
+    }
+
+    pub fn compile(&self, query: &str) -> Result<Regex, TinoError> {
+        match self {
+            SearchMode::Plain => RegexBuilder::new(&regex::escape(query)).build(),
+            SearchMode::IgnoreCase => RegexBuilder::new(&regex::escape(query))
+                .case_insensitive(true)
+                .build(),
+            SearchMode::Regex => RegexBuilder::new(query).build(),
+        }
+        .map_err(TinoError::InvalidSearchQuery)
+    }
+}
+
+/// A line of a TINO file matching a search.
+#[derive(Debug, Clone)]
+pub struct SearchMatch {
+    pub tino_file: TinoFile,
+    /// Starts at 1, like editors do.
+    pub line_number: usize,
+    pub line: String,
+    /// Byte ranges of `line` matched by the query.
+    pub ranges: Vec<(usize, usize)>,
+}
+
+/// Searches `query` in every line of `tino_files`, files that can't be read as text are skipped.
+pub fn search_tino_files(
+    tino_files: &[TinoFile],
+    query: &str,
+    mode: SearchMode,
+) -> Result<Vec<SearchMatch>, TinoError> {
+    let regex = mode.compile(query)?;
+    let mut matches = vec![];
+
+    for tino_file in tino_files {
+        let Ok(content) = fs::read_to_string(&tino_file.path) else {
+            continue;
+        };
+        matches.extend(search_content(tino_file, &content, &regex));
+    }
+
+    Ok(matches)
+}
+
+/// Like [`search_tino_files`], but only reads the files `search_index` says may match.
+///
+/// The index is updated with the changes in `tino_files` and saved first.
+pub fn search_tino_files_indexed(
+    tino_files: &[TinoFile],
+    query: &str,
+    mode: SearchMode,
+    search_index: &mut SearchIndex,
+) -> Result<Vec<SearchMatch>, TinoError> {
+    if search_index.update(tino_files) {
+        search_index.save()?;
+    }
+
+    match search_index.candidates(query, mode) {
+        Some(candidates) => {
+            let candidate_files: Vec<TinoFile> = tino_files
+                .iter()
+                .filter(|tino_file| candidates.contains(&tino_file.path))
+                .cloned()
+                .collect();
+            search_tino_files(&candidate_files, query, mode)
+        }
+        None => search_tino_files(tino_files, query, mode),
+    }
+}
+
+pub fn search_content(tino_file: &TinoFile, content: &str, regex: &Regex) -> Vec<SearchMatch> {
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let ranges: Vec<(usize, usize)> = regex
+                .find_iter(line)
+                .filter(|found| !found.is_empty())
+                .map(|found| (found.start(), found.end()))
+                .collect();
+            if ranges.is_empty() {
+                return None;
+            }
+            Some(SearchMatch {
+                tino_file: tino_file.clone(),
+                line_number: index + 1,
+                line: line.to_string(),
+                ranges,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,402 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fs,
+    path::PathBuf,
+    time::SystemTime,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::app::{search::SearchMode, tino_file::TinoFile, utils::TinoError};
+
+/// Bumped when the format of the index changes, older indexes are rebuilt.
+const SEARCH_INDEX_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexedFile {
+    path: String,
+    modified: Option<SystemTime>,
+    /// Words of the file, rebuilt from `postings` when the index is loaded.
+    #[serde(skip)]
+    tokens: Vec<String>,
+}
+
+/// Inverted index of the words in TINO files, saved in the cache directory.
+///
+/// It's only used to skip files that can't match a search,
+/// the remaining files are still read to find the matching lines.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SearchIndex {
+    version: u32,
+    next_id: u32,
+    files: HashMap<u32, IndexedFile>,
+    /// Lowercase word to the ids of the files having it, sorted to find words by prefix.
+    postings: BTreeMap<String, BTreeSet<u32>>,
+    /// Every suffix of the words of `postings` to the words ending with it,
+    /// to find words by suffix or by a part in the middle.
+    #[serde(skip)]
+    suffixes: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl SearchIndex {
+    pub fn path() -> Result<PathBuf, TinoError> {
+        match dirs::cache_dir() {
+            Some(cache_dir) => Ok(cache_dir.join("tino").join("search-index.json")),
+            None => Err(TinoError::CacheDirNotFound),
+        }
+    }
+
+    pub fn new() -> Self {
+        Self {
+            version: SEARCH_INDEX_VERSION,
+            ..Default::default()
+        }
+    }
+
+    /// Loads the saved index, an empty one is returned if it doesn't exist or can't be read.
+    pub fn load() -> Result<Self, TinoError> {
+        let search_index = fs::read_to_string(Self::path()?)
+            .ok()
+            .and_then(|content| serde_json::from_str::<Self>(&content).ok())
+            .filter(|search_index| search_index.version == SEARCH_INDEX_VERSION);
+
+        Ok(search_index
+            .map(Self::rebuild_lookups)
+            .unwrap_or_else(Self::new))
+    }
+
+    /// Fills what isn't saved, the words of each file and the suffixes of the words.
+    fn rebuild_lookups(mut self) -> Self {
+        let tokens: Vec<String> = self.postings.keys().cloned().collect();
+        for token in tokens {
+            for id in self.postings[&token].iter() {
+                if let Some(indexed_file) = self.files.get_mut(id) {
+                    indexed_file.tokens.push(token.clone());
+                }
+            }
+            self.insert_suffixes(&token);
+        }
+        self
+    }
+
+    pub fn save(&self) -> Result<(), TinoError> {
+        let path = Self::path()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(TinoError::WriteSearchIndexFailed)?;
+        }
+        let content = serde_json::to_string(self).map_err(TinoError::SerializeSearchIndexFailed)?;
+        fs::write(path, content).map_err(TinoError::WriteSearchIndexFailed)
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Indexes new and modified files and forgets removed ones, returns `true` if something changed.
+    pub fn update(&mut self, tino_files: &[TinoFile]) -> bool {
+        let mut changed = false;
+        let current_paths: HashSet<&str> = tino_files
+            .iter()
+            .map(|tino_file| tino_file.path.as_str())
+            .collect();
+
+        let removed_ids: Vec<u32> = self
+            .files
+            .iter()
+            .filter(|(_, indexed_file)| !current_paths.contains(indexed_file.path.as_str()))
+            .map(|(id, _)| *id)
+            .collect();
+        for id in removed_ids {
+            self.remove(id);
+            changed = true;
+        }
+
+        let ids_by_path: HashMap<String, u32> = self
+            .files
+            .iter()
+            .map(|(id, indexed_file)| (indexed_file.path.clone(), *id))
+            .collect();
+        for tino_file in tino_files {
+            let id = ids_by_path.get(&tino_file.path).copied();
+            if let Some(id) = id {
+                if self.files[&id].modified == tino_file.modified && tino_file.modified.is_some() {
+                    continue;
+                }
+                self.remove(id);
+            }
+
+            // NOTE: Files that can't be read as text are indexed without words,
+            // so they aren't read again until they change.
+            let content = fs::read_to_string(&tino_file.path).unwrap_or_default();
+            self.insert(tino_file, &content);
+            changed = true;
+        }
+
+        changed
+    }
+
+    /// Paths of the files that may match `query`, `None` if every file may match.
+    pub fn candidates(&self, query: &str, mode: SearchMode) -> Option<HashSet<String>> {
+        // NOTE: Words can't be taken from a regex, so regex searches read every file.
+        if mode == SearchMode::Regex {
+            return None;
+        }
+        let query_tokens = query_words(query);
+        if query_tokens.is_empty() {
+            return None;
+        }
+
+        // NOTE: The first word of the query can be the end of a bigger word, and the last one
+        // its start, unless the query has something else before or after them.
+        let starts_in_word = query.chars().next().is_some_and(is_word_character);
+        let ends_in_word = query.chars().next_back().is_some_and(is_word_character);
+        let mut candidate_ids: Option<BTreeSet<u32>> = None;
+        for (i, query_token) in query_tokens.iter().enumerate() {
+            let is_first = i == 0 && starts_in_word;
+            let is_last = i == query_tokens.len() - 1 && ends_in_word;
+            let tokens: Vec<&String> = match (is_first, is_last) {
+                (false, false) => self
+                    .postings
+                    .get_key_value(query_token)
+                    .map(|(token, _)| token)
+                    .into_iter()
+                    .collect(),
+                (false, true) => self
+                    .postings
+                    .range::<String, _>(query_token..)
+                    .map(|(token, _)| token)
+                    .take_while(|token| token.starts_with(query_token.as_str()))
+                    .collect(),
+                (true, false) => self
+                    .suffixes
+                    .get(query_token)
+                    .into_iter()
+                    .flatten()
+                    .collect(),
+                (true, true) => self
+                    .suffixes
+                    .range::<String, _>(query_token..)
+                    .take_while(|(suffix, _)| suffix.starts_with(query_token.as_str()))
+                    .flat_map(|(_, tokens)| tokens)
+                    .collect(),
+            };
+            let ids: BTreeSet<u32> = tokens
+                .into_iter()
+                .filter_map(|token| self.postings.get(token))
+                .flatten()
+                .copied()
+                .collect();
+            candidate_ids = Some(match candidate_ids {
+                Some(candidate_ids) => candidate_ids.intersection(&ids).copied().collect(),
+                None => ids,
+            });
+        }
+
+        Some(
+            candidate_ids
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|id| {
+                    self.files
+                        .get(&id)
+                        .map(|indexed_file| indexed_file.path.clone())
+                })
+                .collect(),
+        )
+    }
+
+    fn insert(&mut self, tino_file: &TinoFile, content: &str) {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let tokens: Vec<String> = tokenize(content).into_iter().collect();
+        for token in tokens.iter() {
+            if !self.postings.contains_key(token) {
+                self.insert_suffixes(token);
+            }
+            self.postings.entry(token.clone()).or_default().insert(id);
+        }
+        self.files.insert(
+            id,
+            IndexedFile {
+                path: tino_file.path.clone(),
+                modified: tino_file.modified,
+                tokens,
+            },
+        );
+    }
+
+    fn remove(&mut self, id: u32) {
+        let Some(indexed_file) = self.files.remove(&id) else {
+            return;
+        };
+        for token in indexed_file.tokens {
+            if let Some(ids) = self.postings.get_mut(&token) {
+                ids.remove(&id);
+                if ids.is_empty() {
+                    self.postings.remove(&token);
+                    self.remove_suffixes(&token);
+                }
+            }
+        }
+    }
+
+    fn insert_suffixes(&mut self, token: &str) {
+        for (i, _) in token.char_indices() {
+            self.suffixes
+                .entry(token[i..].to_string())
+                .or_default()
+                .insert(token.to_string());
+        }
+    }
+
+    fn remove_suffixes(&mut self, token: &str) {
+        for (i, _) in token.char_indices() {
+            if let Some(tokens) = self.suffixes.get_mut(&token[i..]) {
+                tokens.remove(token);
+                if tokens.is_empty() {
+                    self.suffixes.remove(&token[i..]);
+                }
+            }
+        }
+    }
+}
+
+fn is_word_character(character: char) -> bool {
+    character.is_alphanumeric() || character == '_'
+}
+
+/// Lowercase words of `query`, in order.
+fn query_words(query: &str) -> Vec<String> {
+    query
+        .split(|character: char| !is_word_character(character))
+        .filter(|token| !str::is_empty(token))
+        .map(|token| token.to_lowercase())
+        .collect()
+}
+
+/// Lowercase words, runs of alphanumeric characters and `_`.
+fn tokenize(text: &str) -> BTreeSet<String> {
+    text.split(|character: char| !is_word_character(character))
+        .filter(|token| !token.is_empty())
+        .map(|token| token.to_lowercase())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::app::utils::tests::TestDir;
+
+    /// Writes a file and returns it as a TINO file modified at `modified` seconds.
+    fn write(test_dir: &TestDir, name: &str, content: &str, modified: u64) -> TinoFile {
+        let path = test_dir.0.join(name);
+        fs::write(&path, content).unwrap();
+        TinoFile {
+            path: path.display().to_string(),
+            modified: Some(SystemTime::UNIX_EPOCH + Duration::from_secs(modified)),
+            ..Default::default()
+        }
+    }
+
+    fn candidates(search_index: &SearchIndex, query: &str) -> Vec<String> {
+        let mut paths: Vec<String> = search_index
+            .candidates(query, SearchMode::Plain)
+            .unwrap()
+            .into_iter()
+            .map(|path| path.rsplit('/').next().unwrap().to_string())
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn update_adds_modified_and_removed_files() {
+        let test_dir = TestDir::new("search-index-update");
+        let a = write(&test_dir, "a.md", "apple banana", 1);
+        let b = write(&test_dir, "b.md", "cherry", 1);
+        let mut search_index = SearchIndex::new();
+
+        assert!(search_index.update(&[a.clone(), b.clone()]));
+        assert_eq!(search_index.len(), 2);
+        assert_eq!(candidates(&search_index, "banana"), vec!["a.md"]);
+        assert!(!search_index.update(&[a.clone(), b.clone()]));
+
+        // NOTE: Files are read again only when their modification time changes.
+        let same_time = write(&test_dir, "a.md", "durian", 1);
+        assert!(!search_index.update(&[same_time, b.clone()]));
+        assert_eq!(candidates(&search_index, "banana"), vec!["a.md"]);
+        let modified = write(&test_dir, "a.md", "durian", 2);
+        assert!(search_index.update(&[modified.clone(), b]));
+        assert!(candidates(&search_index, "banana").is_empty());
+        assert_eq!(candidates(&search_index, "durian"), vec!["a.md"]);
+
+        assert!(search_index.update(&[modified]));
+        assert_eq!(search_index.len(), 1);
+        assert!(candidates(&search_index, "cherry").is_empty());
+        assert!(!search_index.postings.contains_key("cherry"));
+        assert!(!search_index.suffixes.contains_key("erry"));
+    }
+
+    #[test]
+    fn candidates_match_parts_of_words_only_at_the_edges() {
+        let test_dir = TestDir::new("search-index-candidates");
+        let search_index_files = [
+            write(&test_dir, "a.md", "hello world", 1),
+            write(&test_dir, "b.md", "othello worldwide", 1),
+            write(&test_dir, "c.md", "hello big world", 1),
+        ];
+        let mut search_index = SearchIndex::new();
+        search_index.update(&search_index_files);
+
+        assert_eq!(
+            candidates(&search_index, "ell"),
+            vec!["a.md", "b.md", "c.md"]
+        );
+        assert_eq!(
+            candidates(&search_index, "llo wor"),
+            vec!["a.md", "b.md", "c.md"]
+        );
+        assert_eq!(
+            candidates(&search_index, " hello wor"),
+            vec!["a.md", "c.md"]
+        );
+        assert_eq!(
+            candidates(&search_index, "hello world "),
+            vec!["a.md", "c.md"]
+        );
+        assert_eq!(candidates(&search_index, "lo big wo"), vec!["c.md"]);
+        assert_eq!(candidates(&search_index, "lo bi wo"), Vec::<String>::new());
+        assert_eq!(candidates(&search_index, "WORLDW"), vec!["b.md"]);
+    }
+
+    #[test]
+    fn candidates_are_every_file_for_regex_and_empty_queries() {
+        let search_index = SearchIndex::new();
+
+        assert_eq!(search_index.candidates("hel+o", SearchMode::Regex), None);
+        assert_eq!(search_index.candidates(" - ", SearchMode::Plain), None);
+    }
+
+    #[test]
+    fn loaded_indexes_rebuild_words_and_suffixes() {
+        let test_dir = TestDir::new("search-index-load");
+        let a = write(&test_dir, "a.md", "apple", 1);
+        let mut search_index = SearchIndex::new();
+        search_index.update(std::slice::from_ref(&a));
+
+        let saved = serde_json::to_string(&search_index).unwrap();
+        assert!(!saved.contains("tokens"));
+        let mut loaded = serde_json::from_str::<SearchIndex>(&saved)
+            .unwrap()
+            .rebuild_lookups();
+
+        assert_eq!(candidates(&loaded, "ppl"), vec!["a.md"]);
+        let modified = write(&test_dir, "a.md", "pear", 2);
+        loaded.update(&[modified]);
+        assert!(!loaded.postings.contains_key("apple"));
+        assert!(candidates(&loaded, "ppl").is_empty());
+    }
+}
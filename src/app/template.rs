@@ -0,0 +1,132 @@
+use std::sync::LazyLock;
+
+use regex::{Captures, Regex};
+
+/// A `{{name}}` variable, spaces around the name are allowed.
+static VARIABLE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{\{\s*([A-Za-z_]+)\s*\}\}").unwrap());
+
+/// Replaces the `{{name}}` variables of `template` with their values.
+///
+/// Unknown variables are kept as they are. Values in a frontmatter block are written as
+/// double-quoted strings, so `title: {{title}}` stays valid with titles like `Retro: sprint`,
+/// or escaped if the variable is already inside one.
+pub fn fill_template(template: &str, variables: &[(&str, &str)]) -> String {
+    let frontmatter_end = frontmatter_end(template);
+    VARIABLE
+        .replace_all(template, |captures: &Captures| {
+            let name = &captures[1];
+            let Some((_, value)) = variables
+                .iter()
+                .find(|(variable, _)| variable.eq_ignore_ascii_case(name))
+            else {
+                return captures[0].to_string();
+            };
+
+            let start = captures.get(0).map_or(0, |variable| variable.start());
+            if start >= frontmatter_end {
+                return value.to_string();
+            }
+            // NOTE: JSON strings are valid double-quoted strings in YAML and TOML.
+            let quoted_value = serde_json::Value::from(*value).to_string();
+            let line_start = template[..start].rfind('\n').map_or(0, |i| i + 1);
+            if is_inside_double_quotes(&template[line_start..start]) {
+                quoted_value[1..quoted_value.len() - 1].to_string()
+            } else {
+                quoted_value
+            }
+        })
+        .into_owned()
+}
+
+/// Offset where the frontmatter block at the start of `template` ends, 0 if it doesn't
+/// start with one.
+fn frontmatter_end(template: &str) -> usize {
+    let mut lines = template.split_inclusive('\n');
+    let Some(first_line) = lines.next() else {
+        return 0;
+    };
+    let delimiter = first_line.trim_end();
+    if delimiter != "---" && delimiter != "+++" {
+        return 0;
+    }
+
+    let mut offset = first_line.len();
+    for line in lines {
+        if line.trim_end() == delimiter {
+            return offset;
+        }
+        offset += line.len();
+    }
+    0
+}
+
+/// `true` if a double-quoted string is still open at the end of `line_start`.
+fn is_inside_double_quotes(line_start: &str) -> bool {
+    let mut inside = false;
+    let mut escaped = false;
+    for character in line_start.chars() {
+        match character {
+            '\\' if inside && !escaped => escaped = true,
+            '"' if !escaped => inside = !inside,
+            _ => escaped = false,
+        }
+    }
+    inside
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VARIABLES: [(&str, &str); 3] = [
+        ("title", "Retro: sprint \"12\""),
+        ("date", "2026-10-18"),
+        ("time", "10:00"),
+    ];
+
+    #[test]
+    fn fill_template_replaces_known_variables() {
+        assert_eq!(
+            fill_template("# {{title}}\n{{ DATE }} {{time}} {{unknown}}\n", &VARIABLES),
+            "# Retro: sprint \"12\"\n2026-10-18 10:00 {{unknown}}\n"
+        );
+    }
+
+    #[test]
+    fn fill_template_quotes_values_in_the_frontmatter() {
+        let template = "---\ntitle: {{title}}\ncreated: \"{{date}}T{{time}}\"\n---\n# {{title}}\n";
+
+        let filled = fill_template(template, &VARIABLES);
+
+        assert_eq!(
+            filled,
+            "---\ntitle: \"Retro: sprint \\\"12\\\"\"\ncreated: \"2026-10-18T10:00\"\n---\n\
+             # Retro: sprint \"12\"\n"
+        );
+        let frontmatter: serde_yaml_ng::Value =
+            serde_yaml_ng::from_str(filled.split("---\n").nth(1).unwrap()).unwrap();
+        assert_eq!(frontmatter["title"].as_str(), Some("Retro: sprint \"12\""));
+    }
+
+    #[test]
+    fn fill_template_quotes_values_in_toml_frontmatter() {
+        let filled = fill_template("+++\ntitle = {{title}}\n+++\n{{date}}\n", &VARIABLES);
+
+        assert_eq!(
+            filled,
+            "+++\ntitle = \"Retro: sprint \\\"12\\\"\"\n+++\n2026-10-18\n"
+        );
+        let frontmatter: toml::Table =
+            toml::from_str(filled.split("+++\n").nth(1).unwrap()).unwrap();
+        assert_eq!(frontmatter["title"].as_str(), Some("Retro: sprint \"12\""));
+    }
+
+    #[test]
+    fn fill_template_ignores_unclosed_frontmatter() {
+        assert_eq!(
+            fill_template("---\n{{date}}\n", &VARIABLES),
+            "---\n2026-10-18\n"
+        );
+    }
+}
@@ -0,0 +1,432 @@
+use std::{cmp::Ordering, fs, time::SystemTime};
+
+use chrono::{DateTime, FixedOffset, NaiveDateTime};
+use serde::Deserialize;
+
+use crate::app::{
+    checklist::Progress,
+    config_file::{ConfigFile, TinoType},
+    file_name_pattern::Placeholder,
+    frontmatter::Frontmatter,
+};
+
+/// Default format of the timestamp of file names, also read from frontmatter without offset.
+pub const TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+/// Fields parsed back from a file name written with the `[file_name]` pattern, like
+/// `"<title> <timestamp> - <category>.md"`.
+///
+/// Names that don't match the pattern keep the whole file stem as title.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TinoFileName {
+    pub title: String,
+    pub timestamp: Option<NaiveDateTime>,
+    /// Category name, or the raw suffix if it isn't a configured category.
+    pub category: Option<String>,
+}
+
+impl TinoFileName {
+    pub fn parse(file_name: &str, config_file: &ConfigFile) -> Self {
+        let stem = file_name.strip_suffix(".md").unwrap_or(file_name);
+        let Ok(file_name_pattern) = config_file.file_name.pattern() else {
+            return Self::untitled(stem);
+        };
+        let Some(values) = file_name_pattern.parse(stem) else {
+            return Self::untitled(stem);
+        };
+
+        let mut tino_file_name = Self::default();
+        for (placeholder, value) in values {
+            match placeholder {
+                Placeholder::Title => tino_file_name.title = value,
+                Placeholder::Timestamp => {
+                    tino_file_name.timestamp = file_name_pattern.parse_timestamp(&value);
+                }
+                Placeholder::Category => {
+                    // NOTE: Slugified names only match the slug of the category.
+                    let configured_category =
+                        config_file.find_category(&value).ok().or_else(|| {
+                            config_file.categories.items.iter().find(|category| {
+                                config_file.file_name.slug(category.file_name_suffix()) == value
+                            })
+                        });
+                    tino_file_name.category = Some(match configured_category {
+                        Some(configured_category) => configured_category.name.clone(),
+                        None => value,
+                    });
+                }
+                Placeholder::Type => {}
+            }
+        }
+        tino_file_name
+    }
+
+    fn untitled(stem: &str) -> Self {
+        Self {
+            title: stem.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+/// A file inside a TINO directory.
+#[derive(Debug, Default, Clone)]
+pub struct TinoFile {
+    /// Name of the TINO type.
+    pub tino_type: String,
+    /// Label of the TINO type.
+    pub label: String,
+    pub file_name: String,
+    /// Absolute path.
+    pub path: String,
+    pub title: String,
+    /// Creation time, in the configured time zone.
+    pub timestamp: Option<DateTime<FixedOffset>>,
+    pub category: Option<String>,
+    pub modified: Option<SystemTime>,
+    /// Checked checkboxes, only for files of todo types.
+    pub progress: Option<Progress>,
+    /// Tags of the frontmatter.
+    pub tags: Vec<String>,
+    /// Status of the frontmatter.
+    pub status: Option<String>,
+    /// Why the frontmatter couldn't be read, the file name is used instead.
+    pub frontmatter_error: Option<String>,
+}
+
+impl TinoFile {
+    /// Values of the frontmatter take priority over the ones of the file name, files without
+    /// frontmatter, or with one that can't be parsed, only use the file name. Parse errors are
+    /// kept in `frontmatter_error`.
+    pub fn new(
+        tino_type: &TinoType,
+        file_name: String,
+        path: String,
+        modified: Option<SystemTime>,
+        config_file: &ConfigFile,
+    ) -> Self {
+        let mut tino_file_name = TinoFileName::parse(&file_name, config_file);
+        let (frontmatter, frontmatter_error) = match Frontmatter::read(&path) {
+            Ok(frontmatter) => (frontmatter.unwrap_or_default(), None),
+            Err(error) => (Frontmatter::default(), Some(error.to_string())),
+        };
+        if let Some(title) = frontmatter.title.as_ref().filter(|title| !title.is_empty()) {
+            tino_file_name.title = title.clone();
+        }
+        if let Some(category) = frontmatter.category.as_ref().filter(|c| !c.is_empty()) {
+            tino_file_name.category = Some(match config_file.find_category(category) {
+                Ok(configured_category) => configured_category.name.clone(),
+                Err(_) => category.clone(),
+            });
+        }
+
+        // NOTE: File names don't have offsets, their timestamps are in the configured time zone.
+        let timezone = config_file.timezone();
+        let timestamp = frontmatter
+            .created(timezone)
+            .or_else(|| {
+                tino_file_name
+                    .timestamp
+                    .and_then(|timestamp| timezone.localize(timestamp))
+            })
+            .map(|timestamp| timezone.convert(timestamp));
+
+        let progress = if tino_type.todo {
+            fs::read_to_string(&path)
+                .ok()
+                .map(|content| Progress::new(&content))
+        } else {
+            None
+        };
+
+        Self {
+            tino_type: tino_type.name.clone(),
+            label: tino_type.label.clone(),
+            file_name,
+            path,
+            title: tino_file_name.title,
+            timestamp,
+            category: tino_file_name.category,
+            modified,
+            progress,
+            tags: frontmatter.tags,
+            status: frontmatter.status,
+            frontmatter_error,
+        }
+    }
+}
+
+/// Order of the TINO files list.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortMode {
+    /// Newest creation timestamp, from the file name, first.
+    #[default]
+    Created,
+    /// Last modified first.
+    Modified,
+    Title,
+    Category,
+}
+
+impl SortMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SortMode::Created => "created",
+            SortMode::Modified => "modified",
+            SortMode::Title => "title",
+            SortMode::Category => "category",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            SortMode::Created => SortMode::Modified,
+            SortMode::Modified => SortMode::Title,
+            SortMode::Title => SortMode::Category,
+            SortMode::Category => SortMode::Created,
+        }
+    }
+
+    /// Sorts `tino_files`, files missing the field go last.
+    pub fn sort(&self, tino_files: &mut [TinoFile], config_file: &ConfigFile) {
+        let by_title = |a: &TinoFile, b: &TinoFile| {
+            a.title
+                .to_lowercase()
+                .cmp(&b.title.to_lowercase())
+                .then_with(|| a.file_name.cmp(&b.file_name))
+        };
+
+        match self {
+            SortMode::Created => tino_files.sort_by(|a, b| {
+                Self::newest_first(a.timestamp, b.timestamp).then_with(|| by_title(a, b))
+            }),
+            SortMode::Modified => tino_files.sort_by(|a, b| {
+                Self::newest_first(a.modified, b.modified).then_with(|| by_title(a, b))
+            }),
+            SortMode::Title => tino_files.sort_by(by_title),
+            SortMode::Category => tino_files.sort_by(|a, b| {
+                config_file
+                    .category_position(a.category.as_deref())
+                    .cmp(&config_file.category_position(b.category.as_deref()))
+                    .then_with(|| by_title(a, b))
+            }),
+        }
+    }
+
+    fn newest_first<T: Ord>(a: Option<T>, b: Option<T>) -> Ordering {
+        match (a, b) {
+            (Some(a), Some(b)) => b.cmp(&a),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::app::config_file::Category;
+
+    fn category(name: &str, code: Option<&str>) -> Category {
+        Category {
+            name: name.to_string(),
+            code: code.map(String::from),
+            ..Default::default()
+        }
+    }
+
+    fn config_file() -> ConfigFile {
+        let mut config_file = ConfigFile::default();
+        config_file.categories.items = vec![
+            category("Project", Some("P")),
+            category("Client work", None),
+            category("Area", None),
+        ];
+        config_file
+    }
+
+    fn timestamp(day: u32, hour: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, day)
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn parse_reads_the_default_pattern() {
+        assert_eq!(
+            TinoFileName::parse("Release notes 2026-10-18T10:00:00 - P.md", &config_file()),
+            TinoFileName {
+                title: String::from("Release notes"),
+                timestamp: Some(timestamp(18, 10)),
+                category: Some(String::from("Project")),
+            }
+        );
+        assert_eq!(
+            TinoFileName::parse("notes 2026-10-18T10:00:00.md", &config_file()),
+            TinoFileName {
+                title: String::from("notes"),
+                timestamp: Some(timestamp(18, 10)),
+                category: None,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_matches_categories_by_name_or_code() {
+        let config_file = config_file();
+
+        for (file_name, category) in [
+            ("a 2026-10-18T10:00:00 - Area.md", "Area"),
+            ("a 2026-10-18T10:00:00 - area.md", "Area"),
+            ("a 2026-10-18T10:00:00 - p.md", "Project"),
+            ("a 2026-10-18T10:00:00 - Misc.md", "Misc"),
+            ("a 2026-10-18T10:00:00 - P-2.md", "Project"),
+        ] {
+            assert_eq!(
+                TinoFileName::parse(file_name, &config_file).category,
+                Some(String::from(category)),
+                "{file_name}"
+            );
+        }
+    }
+
+    #[test]
+    fn parse_matches_slugified_categories() {
+        let mut config_file = config_file();
+        config_file.file_name.pattern = String::from("{timestamp}_{title}_{category}");
+        config_file.file_name.timestamp_format = String::from("%Y%m%d-%H%M%S");
+        config_file.file_name.slugify = true;
+
+        assert_eq!(
+            TinoFileName::parse("20261018-100000_my-title_client-work.md", &config_file),
+            TinoFileName {
+                title: String::from("my-title"),
+                timestamp: Some(timestamp(18, 10)),
+                category: Some(String::from("Client work")),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_keeps_names_that_dont_match_as_title() {
+        for file_name in ["random.md", "random", "notes 2026-99-99T10:00:00.md"] {
+            let tino_file_name = TinoFileName::parse(file_name, &config_file());
+
+            assert_eq!(
+                tino_file_name.title,
+                file_name.strip_suffix(".md").unwrap_or(file_name)
+            );
+            assert_eq!(tino_file_name.timestamp, None);
+            assert_eq!(tino_file_name.category, None);
+        }
+    }
+
+    fn tino_file(title: &str, category: Option<&str>) -> TinoFile {
+        TinoFile {
+            title: title.to_string(),
+            file_name: format!("{}.md", title),
+            category: category.map(String::from),
+            ..Default::default()
+        }
+    }
+
+    fn titles(tino_files: &[TinoFile]) -> Vec<&str> {
+        tino_files
+            .iter()
+            .map(|tino_file| tino_file.title.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn sort_by_created_puts_the_newest_first_and_missing_last() {
+        let timezone = config_file().timezone();
+        let mut tino_files = vec![
+            tino_file("no timestamp", None),
+            tino_file("old", None),
+            tino_file("new", None),
+            tino_file("also new", None),
+        ];
+        tino_files[1].timestamp = timezone.localize(timestamp(1, 10));
+        tino_files[2].timestamp = timezone.localize(timestamp(18, 10));
+        tino_files[3].timestamp = timezone.localize(timestamp(18, 10));
+
+        SortMode::Created.sort(&mut tino_files, &config_file());
+
+        assert_eq!(
+            titles(&tino_files),
+            vec!["also new", "new", "old", "no timestamp"]
+        );
+    }
+
+    #[test]
+    fn sort_by_modified_puts_the_last_modified_first() {
+        let mut tino_files = vec![
+            tino_file("never", None),
+            tino_file("before", None),
+            tino_file("after", None),
+        ];
+        tino_files[1].modified = Some(SystemTime::UNIX_EPOCH + Duration::from_secs(10));
+        tino_files[2].modified = Some(SystemTime::UNIX_EPOCH + Duration::from_secs(20));
+
+        SortMode::Modified.sort(&mut tino_files, &config_file());
+
+        assert_eq!(titles(&tino_files), vec!["after", "before", "never"]);
+    }
+
+    #[test]
+    fn sort_by_title_ignores_case() {
+        let mut tino_files = vec![
+            tino_file("beta", None),
+            tino_file("Gamma", None),
+            tino_file("Alpha", None),
+        ];
+
+        SortMode::Title.sort(&mut tino_files, &config_file());
+
+        assert_eq!(titles(&tino_files), vec!["Alpha", "beta", "Gamma"]);
+    }
+
+    #[test]
+    fn sort_by_category_follows_the_config() {
+        let mut tino_files = vec![
+            tino_file("none", None),
+            tino_file("zulu", Some("Zulu")),
+            tino_file("area", Some("Area")),
+            tino_file("misc", Some("misc")),
+            tino_file("project b", Some("Project")),
+            tino_file("project a", Some("Project")),
+        ];
+
+        SortMode::Category.sort(&mut tino_files, &config_file());
+
+        assert_eq!(
+            titles(&tino_files),
+            vec!["project a", "project b", "area", "misc", "zulu", "none"]
+        );
+    }
+
+    #[test]
+    fn category_position_puts_unknown_categories_after_the_configured_ones() {
+        let config_file = config_file();
+
+        assert_eq!(
+            config_file.category_position(Some("Client work")),
+            (1, String::new())
+        );
+        assert_eq!(
+            config_file.category_position(Some("Misc")),
+            (3, String::from("misc"))
+        );
+        assert_eq!(
+            config_file.category_position(None),
+            (usize::MAX, String::new())
+        );
+    }
+}
@@ -1,3 +1,11 @@
+use std::{
+    env,
+    fs::{self, OpenOptions},
+    io::{self, ErrorKind, Write},
+    path::{Path, PathBuf},
+    process::{Command, ExitStatus},
+};
+
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -10,10 +18,202 @@ pub enum TinoError {
     ReadConfigFileFailed(std::io::Error),
     #[error("Error while deserializing config file content: {0}")]
     DeserializeConfigFileContentFailed(toml::de::Error),
+    #[error("Error while parsing config file: {0}")]
+    ParseConfigFileFailed(toml_edit::TomlError),
+    #[error("Error while writing config file: {0}")]
+    WriteConfigFileFailed(std::io::Error),
+    #[error("No TINO types configured, add a [[tino_types]] entry or a [tino_dirs] directory.")]
+    NoTinoTypesConfigured,
+    #[error("Categories need a name.")]
+    EmptyCategoryName,
+    #[error("The color of the category {0} isn't valid.")]
+    InvalidCategoryColor(String),
     #[error("A valid category haven't be selected.")]
     NotSelectedCategory,
+    #[error("Error while creating tino file: {0}")]
+    CreateTinoFileFailed(std::io::Error),
     #[error("Error while reading tino file content: {0}")]
     ReadTinoFileFailed(std::io::Error),
     #[error("A file haven't be selected.")]
     NotSelectedTinoFile,
+    #[error("There isn't a TINO type called {0}.")]
+    TinoTypeNotFound(String),
+    #[error("There isn't a category called {0}.")]
+    CategoryNotFound(String),
+    #[error("There isn't a TINO file matching {0}.")]
+    TinoFileNotFound(String),
+    #[error("More than one TINO file match {0}:\n{matches}", matches = .1.join("\n"))]
+    AmbiguousTinoFile(String, Vec<String>),
+    #[error("Invalid search query: {0}")]
+    InvalidSearchQuery(regex::Error),
+    #[error("Cache directory not found.")]
+    CacheDirNotFound,
+    #[error("Error while serializing search index: {0}")]
+    SerializeSearchIndexFailed(serde_json::Error),
+    #[error("Error while writing search index: {0}")]
+    WriteSearchIndexFailed(std::io::Error),
+    #[error("Error while openning editor: {0}")]
+    OpenEditorFailed(std::io::Error),
+    #[error("The editor exited with an error, {0}.")]
+    EditorExitedWithError(ExitStatus),
+    #[error("Error while writing tino file: {0}")]
+    WriteTinoFileFailed(std::io::Error),
+    #[error("There isn't a checkbox in line {0}, the file changed.")]
+    CheckboxNotFound(usize),
+    #[error("Error while watching the {0} directory: {1}")]
+    WatchTinoDirFailed(String, notify::Error),
+    #[error("Error while parsing the frontmatter: {0}")]
+    ParseFrontmatterFailed(String),
+    #[error("Error while serializing the frontmatter: {0}")]
+    SerializeFrontmatterFailed(serde_yaml_ng::Error),
+    #[error("The file name pattern {0} isn't valid, {1}.")]
+    InvalidFileNamePattern(String, String),
+    #[error("The template path {0} isn't valid: {1}")]
+    InvalidTemplatePath(String, softpath::SoftPathError),
+    #[error("Error while reading the template {path}: {1}", path = .0.display())]
+    ReadTemplateFailed(std::path::PathBuf, std::io::Error),
+}
+
+/// Opens `file` with the editor in $EDITOR, or vim if it isn't set.
+///
+/// `line` is passed as `+<line>`, which vim, neovim, nano, emacs and most editors understand.
+pub fn open_editor(file: &str, line: Option<usize>) -> Result<ExitStatus, TinoError> {
+    let editor = env::var("EDITOR").unwrap_or_else(|_| "vim".to_string());
+
+    let mut command = Command::new(editor);
+    if let Some(line) = line {
+        command.arg(format!("+{}", line));
+    }
+    command
+        .arg(file)
+        .status()
+        .map_err(TinoError::OpenEditorFailed)
+}
+
+/// Writes `content` to a temporary file next to `file` and renames it to `file`,
+/// so `file` is never left half written.
+pub fn write_atomically(file: &str, content: &str) -> Result<(), TinoError> {
+    let path = Path::new(file);
+    let temporary_file = path.with_file_name(format!(
+        ".{}.tino-tmp",
+        path.file_name().unwrap_or_default().to_string_lossy()
+    ));
+
+    let result = fs::write(&temporary_file, content)
+        .and_then(|_| {
+            // NOTE: The new file must keep the permissions of the old one.
+            let permissions = fs::metadata(path)?.permissions();
+            fs::set_permissions(&temporary_file, permissions)
+        })
+        .and_then(|_| fs::rename(&temporary_file, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&temporary_file);
+    }
+    result.map_err(TinoError::WriteTinoFileFailed)
+}
+
+/// Highest counter added to the name of a new file when the name is taken.
+const MAX_FILE_NAME_COUNTER: usize = 999;
+
+/// Creates `file_name` in `dir` with `content`, never overwriting a file.
+///
+/// If the name is taken a counter is added before the extension, like `name-2.md`.
+/// Returns the path of the created file.
+pub fn create_new_file(dir: &Path, file_name: &str, content: &str) -> Result<PathBuf, TinoError> {
+    let (stem, extension) = match file_name.rsplit_once('.') {
+        Some((stem, extension)) => (stem, format!(".{}", extension)),
+        None => (file_name, String::new()),
+    };
+
+    for counter in 1..=MAX_FILE_NAME_COUNTER {
+        let path = if counter == 1 {
+            dir.join(file_name)
+        } else {
+            dir.join(format!("{}-{}{}", stem, counter, extension))
+        };
+        let mut file = match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => file,
+            Err(error) if error.kind() == ErrorKind::AlreadyExists => continue,
+            Err(error) => return Err(TinoError::CreateTinoFileFailed(error)),
+        };
+        if let Err(error) = file.write_all(content.as_bytes()) {
+            let _ = fs::remove_file(&path);
+            return Err(TinoError::CreateTinoFileFailed(error));
+        }
+        return Ok(path);
+    }
+    Err(TinoError::CreateTinoFileFailed(io::Error::new(
+        ErrorKind::AlreadyExists,
+        format!(
+            "{} and the next {} names are taken",
+            file_name,
+            MAX_FILE_NAME_COUNTER - 1
+        ),
+    )))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// An empty directory for a test, removed when it's dropped.
+    pub(crate) struct TestDir(pub PathBuf);
+
+    impl TestDir {
+        pub(crate) fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("tino-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+    }
+
+    impl Drop for TestDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn create_new_file_adds_a_counter_to_taken_names() {
+        let test_dir = TestDir::new("create-new-file");
+
+        let first = create_new_file(&test_dir.0, "note.md", "first").unwrap();
+        let second = create_new_file(&test_dir.0, "note.md", "second").unwrap();
+        let third = create_new_file(&test_dir.0, "note.md", "third").unwrap();
+
+        assert_eq!(first, test_dir.0.join("note.md"));
+        assert_eq!(second, test_dir.0.join("note-2.md"));
+        assert_eq!(third, test_dir.0.join("note-3.md"));
+        assert_eq!(fs::read_to_string(&first).unwrap(), "first");
+        assert_eq!(fs::read_to_string(&second).unwrap(), "second");
+        assert_eq!(fs::read_to_string(&third).unwrap(), "third");
+    }
+
+    #[test]
+    fn create_new_file_never_overwrites() {
+        let test_dir = TestDir::new("never-overwrites");
+        fs::write(test_dir.0.join("note"), "old").unwrap();
+        fs::write(test_dir.0.join("note-2"), "old").unwrap();
+
+        let created = create_new_file(&test_dir.0, "note", "new").unwrap();
+
+        assert_eq!(created, test_dir.0.join("note-3"));
+        assert_eq!(fs::read_to_string(test_dir.0.join("note")).unwrap(), "old");
+        assert_eq!(
+            fs::read_to_string(test_dir.0.join("note-2")).unwrap(),
+            "old"
+        );
+    }
+
+    #[test]
+    fn create_new_file_fails_when_every_name_is_taken() {
+        let test_dir = TestDir::new("every-name-taken");
+        fs::write(test_dir.0.join("a.md"), "").unwrap();
+        for counter in 2..=MAX_FILE_NAME_COUNTER {
+            fs::write(test_dir.0.join(format!("a-{}.md", counter)), "").unwrap();
+        }
+
+        assert!(create_new_file(&test_dir.0, "a.md", "").is_err());
+    }
 }
@@ -0,0 +1,80 @@
+use clap::{Parser, Subcommand, ValueEnum};
+
+/// TINO (Todo-Ideas-Notes-Organizer), run it without a subcommand to open the TUI.
+#[derive(Debug, Parser)]
+#[command(name = "tino", version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Commands>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Commands {
+    /// Create a new TINO file.
+    New {
+        /// Name or label of the TINO type.
+        #[arg(short, long = "type")]
+        tino_type: String,
+        /// Name or code of the PARA category.
+        #[arg(short, long)]
+        category: Option<String>,
+        /// Title of the file, words are joined with spaces.
+        title: Vec<String>,
+    },
+    /// List TINO files.
+    List {
+        /// Only list files of this TINO type.
+        #[arg(short, long = "type")]
+        tino_type: Option<String>,
+        /// Only list files of this PARA category.
+        #[arg(short, long)]
+        category: Option<String>,
+        /// Output format.
+        #[arg(short, long, value_enum, default_value_t)]
+        format: OutputFormat,
+    },
+    /// Print the content of a TINO file.
+    Show {
+        /// Path, file name or part of the file name.
+        file: String,
+    },
+    /// Open a TINO file with $EDITOR.
+    Open {
+        /// Path, file name or part of the file name.
+        file: String,
+        /// Line to open the file at.
+        #[arg(short, long)]
+        line: Option<usize>,
+    },
+    /// Search text in the content of every TINO file.
+    Search {
+        query: String,
+        /// Ignore case when matching.
+        #[arg(short, long, conflicts_with = "regex")]
+        ignore_case: bool,
+        /// Use the query as a regular expression.
+        #[arg(short = 'e', long)]
+        regex: bool,
+        /// Only search files of this TINO type.
+        #[arg(short, long = "type")]
+        tino_type: Option<String>,
+        /// Output format.
+        #[arg(short, long, value_enum, default_value_t)]
+        format: OutputFormat,
+    },
+    /// Rebuild the search index from scratch.
+    Reindex,
+}
+
+#[derive(Debug, Default, Clone, Copy, ValueEnum)]
+pub enum OutputFormat {
+    /// The labels shown in the TUI followed by the path.
+    #[default]
+    Plain,
+    /// A JSON array of objects.
+    Json,
+    /// One JSON object per line.
+    Ndjson,
+    /// Tab separated values with a header line.
+    Tsv,
+}
@@ -0,0 +1,228 @@
+use std::{fs, io::Write, path::Path};
+
+use tui_input::Input;
+
+use crate::{
+    app::{
+        config_file::ConfigFile,
+        search::{SearchMode, search_tino_files_indexed},
+        search_index::SearchIndex,
+        tino_file::TinoFile,
+        utils::{TinoError, open_editor},
+    },
+    cli::{
+        args::{Commands, OutputFormat},
+        output::{SearchRecord, TinoFileRecord, print_records, write_stdout},
+    },
+    ratatui_app::{helper_methods::Helpers, types::App},
+};
+
+impl Commands {
+    pub fn run(self, config_file: ConfigFile) -> anyhow::Result<()> {
+        match self {
+            Commands::New {
+                tino_type,
+                category,
+                title,
+            } => new(
+                config_file,
+                &tino_type,
+                category.as_deref(),
+                &title.join(" "),
+            ),
+            Commands::List {
+                tino_type,
+                category,
+                format,
+            } => list(
+                config_file,
+                tino_type.as_deref(),
+                category.as_deref(),
+                format,
+            ),
+            Commands::Show { file } => {
+                let tino_file = find_tino_file(config_file, &file)?;
+                match fs::read_to_string(tino_file) {
+                    Ok(content) => write_stdout(|stdout| stdout.write_all(content.as_bytes())),
+                    Err(error) => Err(TinoError::ReadTinoFileFailed(error).into()),
+                }
+            }
+            Commands::Open { file, line } => {
+                let tino_file = find_tino_file(config_file, &file)?;
+                open_editor(&tino_file, line)?;
+                Ok(())
+            }
+            Commands::Reindex => reindex(config_file),
+            Commands::Search {
+                query,
+                ignore_case,
+                regex,
+                tino_type,
+                format,
+            } => {
+                let search_mode = if regex {
+                    SearchMode::Regex
+                } else if ignore_case {
+                    SearchMode::IgnoreCase
+                } else {
+                    SearchMode::Plain
+                };
+                search(
+                    config_file,
+                    &query,
+                    search_mode,
+                    tino_type.as_deref(),
+                    format,
+                )
+            }
+        }
+    }
+}
+
+fn new(
+    config_file: ConfigFile,
+    tino_type: &str,
+    category: Option<&str>,
+    title: &str,
+) -> anyhow::Result<()> {
+    let tino_type_name = config_file.find_tino_type(tino_type)?.name.clone();
+    let category_name = match category {
+        Some(category) => config_file.find_category(category)?.name.clone(),
+        None => String::new(),
+    };
+
+    // NOTE: Select the type and category like the user would do in the TUI.
+    let mut app = App::new(config_file)?;
+    let type_index = app
+        .config_file
+        .tino_types
+        .iter()
+        .position(|i| i.name == tino_type_name);
+    app.type_state.select(type_index);
+    let category_index = app
+        .category_items
+        .iter()
+        .position(|i| i.name == category_name);
+    app.category_state.select(category_index);
+
+    app.file_name_input = Input::new(title.to_string());
+
+    let tino_dir = match app.selected_tino_type() {
+        Some(tino_type) => tino_type.dir.clone(),
+        None => return Err(TinoError::TinoTypeNotFound(tino_type.to_string()).into()),
+    };
+    let tino_file = app.create_tino_file(&tino_dir)?;
+    for note in tino_file.notes {
+        eprintln!("{}", note);
+    }
+    write_stdout(|stdout| writeln!(stdout, "{}", tino_file.path.display()))
+}
+
+fn list(
+    config_file: ConfigFile,
+    tino_type: Option<&str>,
+    category: Option<&str>,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    let tino_types = match tino_type {
+        Some(tino_type) => vec![config_file.find_tino_type(tino_type)?.clone()],
+        None => config_file.tino_types.clone(),
+    };
+    let category = match category {
+        Some(category) => Some(config_file.find_category(category)?.name.clone()),
+        None => None,
+    };
+
+    let mut tino_files = vec![];
+    for tino_type in tino_types.iter() {
+        tino_files.extend(
+            skip_unreadable(App::get_tino_dir_files(tino_type, &config_file)?)
+                .into_iter()
+                .filter(|tino_file| category.is_none() || tino_file.category == category),
+        );
+    }
+    config_file.list.sort.sort(&mut tino_files, &config_file);
+
+    let records = tino_files
+        .into_iter()
+        .map(TinoFileRecord::new)
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    print_records(&records, format)
+}
+
+fn search(
+    config_file: ConfigFile,
+    query: &str,
+    search_mode: SearchMode,
+    tino_type: Option<&str>,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    let tino_files = match tino_type {
+        Some(tino_type) => skip_unreadable(App::get_tino_dir_files(
+            config_file.find_tino_type(tino_type)?,
+            &config_file,
+        )?),
+        None => skip_unreadable(App::get_tino_files(config_file)?),
+    };
+
+    let mut search_index = SearchIndex::load()?;
+    let records: Vec<SearchRecord> =
+        search_tino_files_indexed(&tino_files, query, search_mode, &mut search_index)?
+            .into_iter()
+            .map(SearchRecord::from)
+            .collect();
+    print_records(&records, format)
+}
+
+fn reindex(config_file: ConfigFile) -> anyhow::Result<()> {
+    let tino_files = skip_unreadable(App::get_tino_files(config_file)?);
+    let mut search_index = SearchIndex::new();
+    search_index.update(&tino_files);
+    search_index.save()?;
+    let search_index_path = SearchIndex::path()?;
+    write_stdout(|stdout| {
+        writeln!(
+            stdout,
+            "Indexed {} files in {}",
+            search_index.len(),
+            search_index_path.display()
+        )
+    })
+}
+
+/// Prints the entries, or frontmatters, that couldn't be read to stderr and keeps the TINO files.
+fn skip_unreadable((tino_files, errors): (Vec<TinoFile>, Vec<anyhow::Error>)) -> Vec<TinoFile> {
+    for error in errors {
+        eprintln!("Warning: {:#}", error);
+    }
+    tino_files
+}
+
+/// Finds a TINO file by its path, its file name or a unique part of its file name.
+fn find_tino_file(config_file: ConfigFile, file: &str) -> anyhow::Result<String> {
+    if Path::new(file).is_file() {
+        return Ok(file.to_string());
+    }
+
+    let tino_files = skip_unreadable(App::get_tino_files(config_file)?);
+
+    if let Some(tino_file) = tino_files
+        .iter()
+        .find(|tino_file| tino_file.file_name == file)
+    {
+        return Ok(tino_file.path.clone());
+    }
+
+    let lowercase_file = file.to_lowercase();
+    let matches: Vec<String> = tino_files
+        .into_iter()
+        .filter(|tino_file| tino_file.file_name.to_lowercase().contains(&lowercase_file))
+        .map(|tino_file| tino_file.path)
+        .collect();
+
+    match matches.len() {
+        0 => Err(TinoError::TinoFileNotFound(file.to_string()).into()),
+        1 => Ok(matches[0].clone()),
+        _ => Err(TinoError::AmbiguousTinoFile(file.to_string(), matches).into()),
+    }
+}
@@ -0,0 +1,176 @@
+use std::{
+    fs,
+    io::{self, ErrorKind, StdoutLock, Write},
+};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Serialize;
+
+use crate::{
+    app::{checklist::Progress, search::SearchMatch, tino_file::TinoFile},
+    cli::args::OutputFormat,
+    ratatui_app::{helper_methods::Helpers, types::App},
+};
+
+/// A TINO file as printed by `tino list`.
+#[derive(Debug, Serialize)]
+pub struct TinoFileRecord {
+    #[serde(skip)]
+    pub label: String,
+    #[serde(rename = "type")]
+    pub tino_type: String,
+    pub category: Option<String>,
+    pub title: String,
+    pub timestamp: Option<String>,
+    pub path: String,
+    pub size: u64,
+    pub mtime: Option<String>,
+    /// Checked checkboxes, only for todo files.
+    pub progress: Option<Progress>,
+    pub tags: Vec<String>,
+    pub status: Option<String>,
+}
+
+impl TinoFileRecord {
+    pub fn new(tino_file: TinoFile) -> anyhow::Result<Self> {
+        let metadata = fs::metadata(&tino_file.path)?;
+
+        Ok(Self {
+            label: App::format_tino_file(&tino_file),
+            tino_type: tino_file.tino_type,
+            category: tino_file.category,
+            title: tino_file.title,
+            timestamp: tino_file
+                .timestamp
+                .map(|timestamp| timestamp.to_rfc3339_opts(SecondsFormat::Secs, false)),
+            path: tino_file.path,
+            size: metadata.len(),
+            mtime: metadata
+                .modified()
+                .ok()
+                .map(|mtime| DateTime::<Utc>::from(mtime).to_rfc3339()),
+            progress: tino_file.progress,
+            tags: tino_file.tags,
+            status: tino_file.status,
+        })
+    }
+}
+
+impl Record for TinoFileRecord {
+    const TSV_HEADER: &'static str =
+        "type\tcategory\ttitle\ttimestamp\tpath\tsize\tmtime\tprogress\ttags\tstatus";
+
+    fn plain(&self) -> String {
+        format!("{}\t{}", self.label, self.path)
+    }
+
+    fn tsv_fields(&self) -> Vec<String> {
+        vec![
+            self.tino_type.clone(),
+            self.category.clone().unwrap_or_default(),
+            self.title.clone(),
+            self.timestamp.clone().unwrap_or_default(),
+            self.path.clone(),
+            self.size.to_string(),
+            self.mtime.clone().unwrap_or_default(),
+            self.progress
+                .map(|progress| progress.to_string())
+                .unwrap_or_default(),
+            self.tags.join(","),
+            self.status.clone().unwrap_or_default(),
+        ]
+    }
+}
+
+/// A line matching `tino search`.
+#[derive(Debug, Serialize)]
+pub struct SearchRecord {
+    #[serde(rename = "type")]
+    pub tino_type: String,
+    pub title: String,
+    pub path: String,
+    pub line: usize,
+    pub text: String,
+}
+
+impl From<SearchMatch> for SearchRecord {
+    fn from(search_match: SearchMatch) -> Self {
+        Self {
+            tino_type: search_match.tino_file.tino_type,
+            title: search_match.tino_file.title,
+            path: search_match.tino_file.path,
+            line: search_match.line_number,
+            text: search_match.line,
+        }
+    }
+}
+
+impl Record for SearchRecord {
+    const TSV_HEADER: &'static str = "type\ttitle\tpath\tline\ttext";
+
+    fn plain(&self) -> String {
+        format!("{}:{}:{}", self.path, self.line, self.text)
+    }
+
+    fn tsv_fields(&self) -> Vec<String> {
+        vec![
+            self.tino_type.clone(),
+            self.title.clone(),
+            self.path.clone(),
+            self.line.to_string(),
+            self.text.clone(),
+        ]
+    }
+}
+
+/// Something `print_records` can print in every [`OutputFormat`].
+pub trait Record: Serialize {
+    const TSV_HEADER: &'static str;
+    fn plain(&self) -> String;
+    fn tsv_fields(&self) -> Vec<String>;
+}
+
+pub fn print_records<R: Record>(records: &[R], format: OutputFormat) -> anyhow::Result<()> {
+    write_stdout(|stdout| {
+        match format {
+            OutputFormat::Plain => {
+                for record in records {
+                    writeln!(stdout, "{}", record.plain())?;
+                }
+            }
+            OutputFormat::Json => {
+                serde_json::to_writer_pretty(&mut *stdout, records)?;
+                writeln!(stdout)?;
+            }
+            OutputFormat::Ndjson => {
+                for record in records {
+                    serde_json::to_writer(&mut *stdout, record)?;
+                    writeln!(stdout)?;
+                }
+            }
+            OutputFormat::Tsv => {
+                writeln!(stdout, "{}", R::TSV_HEADER)?;
+                for record in records {
+                    let fields: Vec<String> = record
+                        .tsv_fields()
+                        .into_iter()
+                        .map(|field| field.replace(['\t', '\n', '\r'], " "))
+                        .collect();
+                    writeln!(stdout, "{}", fields.join("\t"))?;
+                }
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Writes to stdout with `write`.
+///
+/// A closed pipe, like in `tino list | head -1`, isn't an error, the output isn't needed anymore.
+pub fn write_stdout(write: impl FnOnce(&mut StdoutLock) -> io::Result<()>) -> anyhow::Result<()> {
+    let mut stdout = io::stdout().lock();
+    match write(&mut stdout).and_then(|_| stdout.flush()) {
+        Err(error) if error.kind() == ErrorKind::BrokenPipe => Ok(()),
+        result => Ok(result?),
+    }
+}
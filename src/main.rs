@@ -1,10 +1,19 @@
 mod app;
+mod cli;
 mod ratatui_app;
 
-use crate::{app::config_file::ConfigFile, ratatui_app::types::App};
+use clap::Parser;
+
+use crate::{app::config_file::ConfigFile, cli::args::Cli, ratatui_app::types::App};
 
 fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
     let config = ConfigFile::new(false)?;
+
+    if let Some(command) = cli.command {
+        return command.run(config);
+    }
+
     // NOTE: Run ratatui app
     color_eyre::install().unwrap();
     let terminal = ratatui::init();
@@ -0,0 +1,141 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, RecvError, Sender},
+    },
+    thread,
+    time::Duration,
+};
+
+use crossterm::event::{self, Event};
+
+use crate::app::{
+    search::SearchMatch, search_index::SearchIndex, tino_file::TinoFile, utils::TinoError,
+};
+
+/// Time between two ticks.
+const TICK_RATE: Duration = Duration::from_millis(100);
+/// How long the input thread waits for a terminal event before checking if it's paused.
+const INPUT_POLL_TIMEOUT: Duration = Duration::from_millis(50);
+
+/// Events handled by the main loop of [`crate::ratatui_app::types::App`].
+pub enum AppEvent {
+    Terminal(std::io::Result<Event>),
+    Tick,
+    FsChanged(notify::Result<notify::Event>),
+    JobDone(JobResult),
+}
+
+/// Results of the jobs run in the background.
+pub enum JobResult {
+    TinoFilesLoaded {
+        /// See `App::tino_files_generation`.
+        generation: u64,
+        tino_files: Vec<TinoFile>,
+        errors: Vec<anyhow::Error>,
+    },
+    SearchDone {
+        search_index: Option<SearchIndex>,
+        search_results: Result<Vec<SearchMatch>, TinoError>,
+    },
+}
+
+/// Merges terminal events, ticks, filesystem events and the results of background jobs
+/// in a channel.
+#[derive(Debug)]
+pub struct Events {
+    sender: Sender<AppEvent>,
+    receiver: Receiver<AppEvent>,
+    input_paused: Arc<AtomicBool>,
+    input_idle: Arc<AtomicBool>,
+    /// `true` while a tick waits in the channel, so slow frames don't pile them up.
+    tick_pending: Arc<AtomicBool>,
+}
+
+impl Events {
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            sender,
+            receiver,
+            input_paused: Arc::new(AtomicBool::new(false)),
+            input_idle: Arc::new(AtomicBool::new(false)),
+            tick_pending: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Starts the threads that read the terminal events and send the ticks.
+    pub fn start(&self) {
+        let sender = self.sender.clone();
+        let input_paused = self.input_paused.clone();
+        let input_idle = self.input_idle.clone();
+        thread::spawn(move || {
+            loop {
+                if input_paused.load(Ordering::SeqCst) {
+                    input_idle.store(true, Ordering::SeqCst);
+                    thread::sleep(INPUT_POLL_TIMEOUT);
+                    continue;
+                }
+                let terminal_event = match event::poll(INPUT_POLL_TIMEOUT) {
+                    Ok(false) => continue,
+                    Ok(true) => event::read(),
+                    Err(error) => Err(error),
+                };
+                if sender.send(AppEvent::Terminal(terminal_event)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let sender = self.sender.clone();
+        let tick_pending = self.tick_pending.clone();
+        thread::spawn(move || {
+            loop {
+                thread::sleep(TICK_RATE);
+                if tick_pending.swap(true, Ordering::SeqCst) {
+                    continue;
+                }
+                if sender.send(AppEvent::Tick).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+
+    /// Waits for the next event.
+    pub fn next(&self) -> Result<AppEvent, RecvError> {
+        let app_event = self.receiver.recv()?;
+        if let AppEvent::Tick = app_event {
+            self.tick_pending.store(false, Ordering::SeqCst);
+        }
+        Ok(app_event)
+    }
+
+    pub fn sender(&self) -> Sender<AppEvent> {
+        self.sender.clone()
+    }
+
+    /// Runs `job` in another thread, its result is sent as [`AppEvent::JobDone`].
+    pub fn spawn_job(&self, job: impl FnOnce() -> JobResult + Send + 'static) {
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            let _ = sender.send(AppEvent::JobDone(job()));
+        });
+    }
+
+    /// Stops reading terminal events, so another program, like the editor, can read them.
+    ///
+    /// Returns when the input thread is no longer reading.
+    pub fn pause_input(&self) {
+        self.input_idle.store(false, Ordering::SeqCst);
+        self.input_paused.store(true, Ordering::SeqCst);
+        while !self.input_idle.load(Ordering::SeqCst) {
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    pub fn resume_input(&self) {
+        self.input_paused.store(false, Ordering::SeqCst);
+    }
+}
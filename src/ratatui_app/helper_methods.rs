@@ -1,20 +1,43 @@
 use std::{
-    ffi::OsString,
+    collections::HashMap,
     fs::{self, File},
+    io::Read,
+    time::Instant,
 };
 
 use crate::{
-    app::{config_file::ConfigFile, utils::TinoError},
-    ratatui_app::types::{App, TinoFileTypes},
+    app::{
+        agenda::{AgendaItem, collect_agenda},
+        checklist::{Checkbox, add_next_recurrence, parse_checkboxes, toggle_checkbox},
+        config_file::{Category, ConfigFile, TinoType},
+        file_name_pattern::sanitize,
+        frontmatter::Frontmatter,
+        search::{SearchMatch, search_tino_files_indexed},
+        search_index::SearchIndex,
+        template::fill_template,
+        tino_file::TinoFile,
+        utils::{TinoError, create_new_file, write_atomically},
+    },
+    ratatui_app::{
+        events::{AppEvent, JobResult},
+        markdown::{RenderedMarkdown, render_markdown, wrapped_row},
+        types::{AgendaRow, App, CreatedTinoFile, RenderedPreview, StatusMessage, TinoFilesRow},
+    },
 };
-use chrono::Utc;
+use chrono::{DateTime, FixedOffset, Local, SecondsFormat};
+use fuzzy_matcher::{FuzzyMatcher, skim::SkimMatcherV2};
+use notify::{EventKind, RecursiveMode, Watcher};
 use softpath::prelude::*;
 
+/// Number of errors kept in the error log.
+const ERROR_LOG_SIZE: usize = 50;
+
 pub trait Helpers {
-    fn generate_file_name(&mut self) -> Result<String, TinoError>;
+    fn generate_file_name(&mut self, timestamp: DateTime<FixedOffset>)
+    -> Result<String, TinoError>;
     fn quit(&mut self);
-    fn selected_type(&self) -> Option<&str>;
-    fn selected_category(&self) -> Option<&str>;
+    fn selected_tino_type(&self) -> Option<&TinoType>;
+    fn selected_category(&self) -> Option<&Category>;
     fn selected_tino_file(&self) -> Option<&str>;
     fn type_next(&mut self);
     fn type_previous(&mut self);
@@ -22,45 +45,85 @@ pub trait Helpers {
     fn category_previous(&mut self);
     fn tino_file_next(&mut self);
     fn tino_file_previous(&mut self);
-    fn get_tino_files(config_file: ConfigFile) -> anyhow::Result<Vec<(String, String)>>;
+    fn selected_tino_files_row(&self) -> Option<&TinoFilesRow>;
+    fn select_tino_file(&mut self, tino_file: &str);
+    fn update_tino_files_rows(&mut self);
+    fn load_tino_files(config_file: &ConfigFile) -> (Vec<TinoFile>, Vec<anyhow::Error>);
+    fn set_tino_files(&mut self, tino_files: Vec<TinoFile>);
+    fn reload_tino_files(&mut self);
+    fn reload_tino_files_in_background(&mut self);
+    fn watch_tino_dirs(&mut self);
+    fn handle_fs_event(&mut self, fs_event: notify::Result<notify::Event>);
+    fn report_error(&mut self, error: anyhow::Error);
+    fn report_info(&mut self, message: String);
+    fn toggle_selected_group(&mut self);
+    fn tino_file_group(&self, tino_file: &TinoFile) -> String;
+    fn get_tino_files(
+        config_file: ConfigFile,
+    ) -> anyhow::Result<(Vec<TinoFile>, Vec<anyhow::Error>)>;
     fn get_tino_dir_files(
-        tino_dir: String,
-        tino_file_type: TinoFileTypes,
-    ) -> anyhow::Result<Vec<(String, String)>>;
-    fn format_tino_file(tino_file_type: TinoFileTypes, tino_file_name: OsString) -> String;
+        tino_type: &TinoType,
+        config_file: &ConfigFile,
+    ) -> anyhow::Result<(Vec<TinoFile>, Vec<anyhow::Error>)>;
+    fn format_tino_file(tino_file: &TinoFile) -> String;
+    fn read_preview(tino_file: &str, max_bytes: u64) -> Result<String, TinoError>;
     fn get_file_content(&self) -> Result<String, TinoError>;
-    fn create_tino_file(&mut self, tino_dir: &str) -> anyhow::Result<()>;
+    fn preview_file(&mut self, tino_file: &str, content: String);
+    fn preview_selected_tino_file(&mut self) -> Result<(), TinoError>;
+    fn request_preview(&mut self);
+    fn run_search(&mut self);
+    fn start_search(&mut self, query: String);
+    fn set_search_results(&mut self, search_results: Vec<SearchMatch>) -> Result<(), TinoError>;
+    fn selected_search_match(&self) -> Option<&SearchMatch>;
+    fn search_next(&mut self);
+    fn search_previous(&mut self);
+    fn preview_search_match(&mut self) -> Result<(), TinoError>;
+    fn preview_file_at_line(
+        &mut self,
+        tino_file: &str,
+        line_number: usize,
+    ) -> Result<(), TinoError>;
+    fn load_agenda(&mut self);
+    fn selected_agenda_item(&self) -> Option<&AgendaItem>;
+    fn agenda_next(&mut self);
+    fn agenda_previous(&mut self);
+    fn preview_agenda_item(&mut self) -> Result<(), TinoError>;
+    fn refresh_preview(&mut self) -> Result<(), TinoError>;
+    fn rendered_preview(&mut self) -> &RenderedMarkdown;
+    fn preview_line(&mut self, line_index: usize) -> usize;
+    fn previewed_checkboxes(&self) -> Vec<Checkbox>;
+    fn checkbox_next(&mut self);
+    fn checkbox_previous(&mut self);
+    fn scroll_to_checkbox(&mut self);
+    fn toggle_selected_checkbox(&mut self) -> Result<(), TinoError>;
+    fn editor_target(&self) -> Option<(String, Option<usize>)>;
+    fn create_tino_file(&mut self, tino_dir: &str) -> anyhow::Result<CreatedTinoFile>;
 }
 
 impl Helpers for App {
-    fn generate_file_name(&mut self) -> Result<String, TinoError> {
+    /// Name of a new file with the title in the File name element, following the
+    /// `[file_name]` options.
+    fn generate_file_name(
+        &mut self,
+        timestamp: DateTime<FixedOffset>,
+    ) -> Result<String, TinoError> {
         let user_input = self.file_name_input.value_and_reset().trim().to_string();
-        let file_name: String;
-
-        let now = Utc::now();
-        let timestamp = now.format("%Y-%m-%dT%H:%M:%S").to_string();
 
-        if user_input.is_empty() && self.selected_category() == Some("") {
-            file_name = format!("{}.md", timestamp);
-        } else if user_input.is_empty() {
-            match self.selected_category() {
-                Some(selected_category) => {
-                    file_name = format!("{} - {}.md", timestamp, selected_category)
-                }
-                None => return Err(TinoError::NotSelectedCategory),
-            }
-        } else if self.selected_category() == Some("") {
-            file_name = format!("{} {}.md", user_input, timestamp);
-        } else {
-            match self.selected_category() {
-                Some(selected_category) => {
-                    file_name = format!("{} {} - {}.md", user_input, timestamp, selected_category)
-                }
-                None => return Err(TinoError::NotSelectedCategory),
-            }
-        }
+        let selected_category = match self.selected_category() {
+            Some(selected_category) => selected_category,
+            None => return Err(TinoError::NotSelectedCategory),
+        };
+        let tino_type = self
+            .selected_tino_type()
+            .map(|tino_type| tino_type.name.as_str())
+            .unwrap_or_default();
 
-        Ok(file_name)
+        self.config_file.file_name.file_name(
+            &user_input,
+            timestamp,
+            selected_category.file_name_suffix(),
+            tino_type,
+        )
     }
 
     /// Set running to false to quit the application.
@@ -68,25 +131,29 @@ impl Helpers for App {
         self.running = false;
     }
 
-    fn selected_type(&self) -> Option<&str> {
+    fn selected_tino_type(&self) -> Option<&TinoType> {
         self.type_state
             .selected()
-            .map(|i| self.type_items[i].as_str())
+            .and_then(|i| self.config_file.tino_types.get(i))
     }
 
-    fn selected_category(&self) -> Option<&str> {
+    fn selected_category(&self) -> Option<&Category> {
         self.category_state
             .selected()
-            .map(|i| self.category_items[i].as_str())
+            .and_then(|i| self.category_items.get(i))
     }
 
     fn selected_tino_file(&self) -> Option<&str> {
-        self.tino_files_state
-            .selected()
-            .map(|i| self.tino_files[i].1.as_str())
+        match self.selected_tino_files_row() {
+            Some(TinoFilesRow::File(index)) => Some(self.tino_files[*index].path.as_str()),
+            _ => None,
+        }
     }
 
     fn type_next(&mut self) {
+        if self.type_items.is_empty() {
+            return;
+        }
         let i = match self.type_state.selected() {
             Some(i) => {
                 if i >= self.type_items.len() - 1 {
@@ -101,6 +168,9 @@ impl Helpers for App {
     }
 
     fn type_previous(&mut self) {
+        if self.type_items.is_empty() {
+            return;
+        }
         let i = match self.type_state.selected() {
             Some(i) => {
                 if i == 0 {
@@ -143,9 +213,12 @@ impl Helpers for App {
     }
 
     fn tino_file_next(&mut self) {
+        if self.tino_files_rows.is_empty() {
+            return;
+        }
         let i = match self.tino_files_state.selected() {
             Some(i) => {
-                if i >= self.tino_files.len() - 1 {
+                if i >= self.tino_files_rows.len() - 1 {
                     0
                 } else {
                     i + 1
@@ -154,13 +227,17 @@ impl Helpers for App {
             None => 0,
         };
         self.tino_files_state.select(Some(i));
+        self.request_preview();
     }
 
     fn tino_file_previous(&mut self) {
+        if self.tino_files_rows.is_empty() {
+            return;
+        }
         let i = match self.tino_files_state.selected() {
             Some(i) => {
                 if i == 0 {
-                    self.tino_files.len() - 1
+                    self.tino_files_rows.len() - 1
                 } else {
                     i - 1
                 }
@@ -168,85 +245,900 @@ impl Helpers for App {
             None => 0,
         };
         self.tino_files_state.select(Some(i));
+        self.request_preview();
     }
 
-    fn get_tino_files(config_file: ConfigFile) -> anyhow::Result<Vec<(String, String)>> {
+    fn selected_tino_files_row(&self) -> Option<&TinoFilesRow> {
+        self.tino_files_state
+            .selected()
+            .and_then(|i| self.tino_files_rows.get(i))
+    }
+
+    fn select_tino_file(&mut self, tino_file: &str) {
+        let row = self.tino_files_rows.iter().position(|row| match row {
+            TinoFilesRow::File(index) => self.tino_files[*index].path == tino_file,
+            TinoFilesRow::Header(..) => false,
+        });
+        if row.is_some() {
+            self.tino_files_state.select(row);
+        }
+    }
+
+    /// Sorts `tino_files` and builds the rows of the TINO files list,
+    /// keeping the selected file or group selected.
+    fn update_tino_files_rows(&mut self) {
+        // NOTE: Rows are rebuilt, so the selection is kept by group name or file path.
+        let selected_group = match self.selected_tino_files_row() {
+            Some(TinoFilesRow::Header(group, _)) => Some(group.clone()),
+            _ => None,
+        };
+        let selected_tino_file = self.selected_tino_file().map(String::from);
+
+        let mut tino_files = std::mem::take(&mut self.tino_files);
+        self.config_file
+            .list
+            .sort
+            .sort(&mut tino_files, &self.config_file);
+        if self.config_file.list.group {
+            // NOTE: Stable sort, files keep the order of the sort mode inside each group.
+            tino_files.sort_by_key(|tino_file| {
+                (
+                    self.config_file
+                        .tino_types
+                        .iter()
+                        .position(|tino_type| tino_type.name == tino_file.tino_type),
+                    self.config_file
+                        .category_position(tino_file.category.as_deref()),
+                )
+            });
+        }
+        self.tino_files = tino_files;
+
+        self.filter_matches = HashMap::new();
+        let filter = self.filter_input.value().trim();
+        if !filter.is_empty() {
+            let matcher = SkimMatcherV2::default();
+            for (index, tino_file) in self.tino_files.iter().enumerate() {
+                if let Some((_, indices)) = matcher.fuzzy_indices(&tino_file.title, filter) {
+                    self.filter_matches.insert(index, indices);
+                }
+            }
+        }
+        let is_filtered = |index: usize| {
+            (filter.is_empty() || self.filter_matches.contains_key(&index))
+                && self.todo_filter.matches(self.tino_files[index].progress)
+        };
+
+        let groups: Vec<(usize, String)> = self
+            .tino_files
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| is_filtered(*index))
+            .map(|(index, tino_file)| (index, self.tino_file_group(tino_file)))
+            .collect();
+        let mut group_sizes: HashMap<&str, usize> = HashMap::new();
+        for (_, group) in groups.iter() {
+            *group_sizes.entry(group.as_str()).or_default() += 1;
+        }
+
+        self.tino_files_rows = vec![];
+        let mut current_group: Option<&str> = None;
+        for (index, group) in groups.iter() {
+            if self.config_file.list.group {
+                if current_group != Some(group.as_str()) {
+                    self.tino_files_rows.push(TinoFilesRow::Header(
+                        group.clone(),
+                        group_sizes[group.as_str()],
+                    ));
+                    current_group = Some(group.as_str());
+                }
+                if self.collapsed_groups.contains(group) {
+                    continue;
+                }
+            }
+            self.tino_files_rows.push(TinoFilesRow::File(*index));
+        }
+
+        let row = self.tino_files_rows.iter().position(|row| match row {
+            TinoFilesRow::Header(group, _) => selected_group.as_ref() == Some(group),
+            TinoFilesRow::File(index) => {
+                selected_tino_file.as_ref() == Some(&self.tino_files[*index].path)
+            }
+        });
+        self.tino_files_state.select(match row {
+            Some(row) => Some(row),
+            None if self.tino_files_rows.is_empty() => None,
+            None => Some(
+                self.tino_files_state
+                    .selected()
+                    .unwrap_or(0)
+                    .min(self.tino_files_rows.len() - 1),
+            ),
+        });
+    }
+
+    /// Reads the TINO directories again, keeping the selected file selected.
+    ///
+    /// Directories that can't be read are reported and skipped.
+    fn reload_tino_files(&mut self) {
+        self.tino_files_generation += 1;
+        let (tino_files, errors) = Self::load_tino_files(&self.config_file);
+        for error in errors {
+            self.report_error(error);
+        }
+        self.set_tino_files(tino_files);
+    }
+
+    /// Like `reload_tino_files`, but the directories are read in another thread.
+    fn reload_tino_files_in_background(&mut self) {
+        self.tino_files_generation += 1;
+        let generation = self.tino_files_generation;
+        let config_file = self.config_file.clone();
+        self.events.spawn_job(move || {
+            let (tino_files, errors) = Self::load_tino_files(&config_file);
+            JobResult::TinoFilesLoaded {
+                generation,
+                tino_files,
+                errors,
+            }
+        });
+    }
+
+    /// Reads every TINO directory, directories that can't be read are returned as errors.
+    fn load_tino_files(config_file: &ConfigFile) -> (Vec<TinoFile>, Vec<anyhow::Error>) {
         let mut tino_files = vec![];
+        let mut errors = vec![];
+        for tino_type in config_file.tino_types.iter() {
+            match Self::get_tino_dir_files(tino_type, config_file) {
+                Ok((tino_dir_files, tino_dir_errors)) => {
+                    tino_files.extend(tino_dir_files);
+                    errors.extend(tino_dir_errors);
+                }
+                Err(error) => errors.push(error.context(format!(
+                    "Can't read the {} directory {}",
+                    tino_type.name, tino_type.dir
+                ))),
+            }
+        }
+        (tino_files, errors)
+    }
 
-        tino_files.extend(Self::get_tino_dir_files(
-            config_file.tino_dirs.todos_dir,
-            TinoFileTypes::Todo,
-        )?);
-        tino_files.extend(Self::get_tino_dir_files(
-            config_file.tino_dirs.ideas_dir,
-            TinoFileTypes::Idea,
-        )?);
-        tino_files.extend(Self::get_tino_dir_files(
-            config_file.tino_dirs.notes_dir,
-            TinoFileTypes::Note,
-        )?);
-        tino_files.extend(Self::get_tino_dir_files(
-            config_file.tino_dirs.academic_notes_dir,
-            TinoFileTypes::AcademicNote,
-        )?);
-
-        Ok(tino_files)
-    }
-
-    fn format_tino_file(tino_file_type: TinoFileTypes, tino_file_name: OsString) -> String {
-        match tino_file_type {
-            TinoFileTypes::Todo => format!("TODO | {}", tino_file_name.display()),
-            TinoFileTypes::Idea => format!("IDEA | {}", tino_file_name.display()),
-            TinoFileTypes::Note => format!("NOTE | {}", tino_file_name.display()),
-            TinoFileTypes::AcademicNote => format!("ACAD. NOTE | {}", tino_file_name.display()),
+    /// Replaces the TINO files, keeping the selected file selected.
+    fn set_tino_files(&mut self, tino_files: Vec<TinoFile>) {
+        let selected_tino_file = self.selected_tino_file().map(String::from);
+        self.tino_files = tino_files;
+
+        self.tino_files_rows = vec![];
+        self.update_tino_files_rows();
+        if let Some(selected_tino_file) = selected_tino_file {
+            self.select_tino_file(&selected_tino_file);
+        }
+        if self.show_agenda {
+            self.load_agenda();
         }
     }
+
+    /// Watches the TINO directories, changes are sent as `AppEvent::FsChanged`.
+    fn watch_tino_dirs(&mut self) {
+        let sender = self.events.sender();
+        let watcher = notify::recommended_watcher(move |fs_event| {
+            let _ = sender.send(AppEvent::FsChanged(fs_event));
+        });
+        let mut watcher = match watcher {
+            Ok(watcher) => watcher,
+            Err(error) => {
+                self.report_error(
+                    TinoError::WatchTinoDirFailed(String::from("TINO"), error).into(),
+                );
+                return;
+            }
+        };
+        for tino_type in self.config_file.tino_types.clone().iter() {
+            // NOTE: Directories are expanded like when they are read, so `~/todos` works.
+            let tino_dir = match tino_type.dir.as_str().into_path() {
+                Ok(tino_dir) => tino_dir.canonicalize().map_err(notify::Error::io),
+                Err(error) => Err(notify::Error::generic(&error.to_string())),
+            };
+            if let Err(error) =
+                tino_dir.and_then(|tino_dir| watcher.watch(&tino_dir, RecursiveMode::NonRecursive))
+            {
+                self.report_error(
+                    TinoError::WatchTinoDirFailed(tino_type.name.clone(), error).into(),
+                );
+            }
+        }
+        self.watcher = Some(watcher);
+    }
+
+    /// Marks the TINO files as changed, they are reloaded on a tick after the changes stop.
+    fn handle_fs_event(&mut self, fs_event: notify::Result<notify::Event>) {
+        match fs_event {
+            // NOTE: Reading files, like the preview does, isn't a change.
+            Ok(fs_event) if matches!(fs_event.kind, EventKind::Access(_)) => {}
+            Ok(_) => self.fs_changed_at = Some(Instant::now()),
+            Err(error) => {
+                self.report_error(TinoError::WatchTinoDirFailed(String::from("TINO"), error).into())
+            }
+        }
+    }
+
+    /// Shows the error in the status bar and keeps it in the error log.
+    fn report_error(&mut self, error: anyhow::Error) {
+        let message = format!("{:#}", error);
+        self.error_log.push_front((Local::now(), message.clone()));
+        self.error_log.truncate(ERROR_LOG_SIZE);
+        self.status_message = Some(StatusMessage {
+            text: message,
+            is_error: true,
+            shown_at: Instant::now(),
+        });
+    }
+
+    fn report_info(&mut self, message: String) {
+        self.status_message = Some(StatusMessage {
+            text: message,
+            is_error: false,
+            shown_at: Instant::now(),
+        });
+    }
+
+    fn toggle_selected_group(&mut self) {
+        if let Some(TinoFilesRow::Header(group, _)) = self.selected_tino_files_row().cloned() {
+            if !self.collapsed_groups.remove(&group) {
+                self.collapsed_groups.insert(group);
+            }
+            self.update_tino_files_rows();
+        }
+    }
+
+    fn tino_file_group(&self, tino_file: &TinoFile) -> String {
+        format!(
+            "{} / {}",
+            tino_file.tino_type,
+            tino_file.category.as_deref().unwrap_or("No category")
+        )
+    }
+
+    fn get_tino_files(
+        config_file: ConfigFile,
+    ) -> anyhow::Result<(Vec<TinoFile>, Vec<anyhow::Error>)> {
+        let mut tino_files = vec![];
+        let mut errors = vec![];
+
+        for tino_type in config_file.tino_types.iter() {
+            let (tino_dir_files, tino_dir_errors) =
+                Self::get_tino_dir_files(tino_type, &config_file)?;
+            tino_files.extend(tino_dir_files);
+            errors.extend(tino_dir_errors);
+        }
+
+        Ok((tino_files, errors))
+    }
+
+    fn format_tino_file(tino_file: &TinoFile) -> String {
+        format!("{} | {}", tino_file.label, tino_file.file_name)
+    }
+
+    /// Reads the first `max_bytes` of a file, with a note at the end if the rest is cut.
+    fn read_preview(tino_file: &str, max_bytes: u64) -> Result<String, TinoError> {
+        let file = File::open(tino_file).map_err(TinoError::ReadTinoFileFailed)?;
+        let size = file
+            .metadata()
+            .map_err(TinoError::ReadTinoFileFailed)?
+            .len();
+
+        let mut bytes = vec![];
+        file.take(max_bytes)
+            .read_to_end(&mut bytes)
+            .map_err(TinoError::ReadTinoFileFailed)?;
+        let mut content = String::from_utf8_lossy(&bytes).into_owned();
+        if size > max_bytes {
+            content.push_str(&format!(
+                "\n\n... Only the first {} of {} bytes are previewed.",
+                max_bytes, size
+            ));
+        }
+        Ok(content)
+    }
+
     fn get_file_content(&self) -> Result<String, TinoError> {
         match self.selected_tino_file() {
-            Some(tino_file) => {
-                match fs::read_to_string(tino_file) {
-                    Ok(content) => return Ok(content),
-                    Err(error) => return Err(TinoError::ReadTinoFileFailed(error)),
-                };
-            }
+            Some(tino_file) => Self::read_preview(tino_file, self.config_file.preview.max_bytes),
             None => Err(TinoError::NotSelectedTinoFile),
         }
     }
-    fn create_tino_file(&mut self, tino_dir: &str) -> anyhow::Result<()> {
-        let file_name = match self.generate_file_name() {
-            Ok(generated_file_name) => generated_file_name,
-            Err(error) => return Err(error.into()),
+
+    /// Shows `content` in the preview, remembering the scroll position of the previous file
+    /// and restoring the one of `tino_file`.
+    fn preview_file(&mut self, tino_file: &str, content: String) {
+        if let Some(previewed_file) = self.previewed_file.take() {
+            self.preview_scroll_positions
+                .insert(previewed_file, self.scroll_position);
+        }
+        if self.previewed_file.as_deref() != Some(tino_file) {
+            self.checkbox_cursor = None;
+        }
+        self.file_to_preview = content;
+        self.previewed_file = Some(tino_file.to_string());
+        self.scroll_position = self
+            .preview_scroll_positions
+            .get(tino_file)
+            .copied()
+            .unwrap_or_default();
+    }
+
+    fn preview_selected_tino_file(&mut self) -> Result<(), TinoError> {
+        self.preview_requested_at = None;
+        let Some(tino_file) = self.selected_tino_file().map(String::from) else {
+            return Ok(());
         };
+        let content = self.get_file_content()?;
+        self.preview_file(&tino_file, content);
+        Ok(())
+    }
 
-        let bufpath = match tino_dir.into_path() {
-            Ok(path) => match path.canonicalize() {
-                Ok(extended_path) => extended_path.join(file_name),
-                Err(error) => return Err(error.into()),
-            },
-            Err(error) => return Err(error.into()),
+    /// Previews the selected TINO file after a short delay, if auto-preview is on.
+    ///
+    /// The delay starts again with each request, so moving fast through the list doesn't read
+    /// every file on the way.
+    fn request_preview(&mut self) {
+        if !self.config_file.preview.auto || self.show_search_results || self.show_agenda {
+            return;
+        }
+        let selected_tino_file = self.selected_tino_file();
+        if selected_tino_file.is_some() && selected_tino_file != self.previewed_file.as_deref() {
+            self.preview_requested_at = Some(Instant::now());
+        }
+    }
+    /// Searches the query in another thread, the index is loaded with the first search.
+    fn run_search(&mut self) {
+        let query = self.search_input.value().to_string();
+        if query.is_empty() {
+            self.queued_search = None;
+            if let Err(error) = self.set_search_results(vec![]) {
+                self.report_error(error.into());
+            }
+            return;
+        }
+        // NOTE: The index is moved to the search thread, so only one search runs at a time,
+        // the latest query submitted meanwhile runs after it.
+        if self.search_running {
+            self.queued_search = Some(query);
+            return;
+        }
+        self.start_search(query);
+    }
+
+    /// Searches `query` in a background job, the results are sent as `JobResult::SearchDone`.
+    fn start_search(&mut self, query: String) {
+        self.search_running = true;
+
+        let search_index = self.search_index.take();
+        let tino_files = self.tino_files.clone();
+        let search_mode = self.search_mode;
+        self.events.spawn_job(move || {
+            let mut search_index = match search_index {
+                Some(search_index) => search_index,
+                None => match SearchIndex::load() {
+                    Ok(search_index) => search_index,
+                    Err(error) => {
+                        return JobResult::SearchDone {
+                            search_index: None,
+                            search_results: Err(error),
+                        };
+                    }
+                },
+            };
+            let search_results =
+                search_tino_files_indexed(&tino_files, &query, search_mode, &mut search_index);
+            JobResult::SearchDone {
+                search_index: Some(search_index),
+                search_results,
+            }
+        });
+    }
+
+    fn set_search_results(&mut self, search_results: Vec<SearchMatch>) -> Result<(), TinoError> {
+        self.search_results = search_results;
+        self.search_state.select(if self.search_results.is_empty() {
+            None
+        } else {
+            Some(0)
+        });
+        self.preview_search_match()
+    }
+
+    fn selected_search_match(&self) -> Option<&SearchMatch> {
+        self.search_state
+            .selected()
+            .and_then(|i| self.search_results.get(i))
+    }
+
+    fn search_next(&mut self) {
+        if self.search_results.is_empty() {
+            return;
+        }
+        let i = match self.search_state.selected() {
+            Some(i) => {
+                if i >= self.search_results.len() - 1 {
+                    0
+                } else {
+                    i + 1
+                }
+            }
+            None => 0,
         };
+        self.search_state.select(Some(i));
+    }
 
-        if let Err(error) = File::create(bufpath) {
-            return Err(error.into());
+    fn search_previous(&mut self) {
+        if self.search_results.is_empty() {
+            return;
         }
-        self.tino_files = Self::get_tino_files(self.config_file.clone())?;
+        let i = match self.search_state.selected() {
+            Some(i) => {
+                if i == 0 {
+                    self.search_results.len() - 1
+                } else {
+                    i - 1
+                }
+            }
+            None => 0,
+        };
+        self.search_state.select(Some(i));
+    }
+
+    /// Shows the file of the selected search match in the preview, scrolled to the matched line.
+    fn preview_search_match(&mut self) -> Result<(), TinoError> {
+        let Some(search_match) = self.selected_search_match() else {
+            return Ok(());
+        };
+        let line_number = search_match.line_number;
+        let tino_file = search_match.tino_file.path.clone();
+        self.preview_file_at_line(&tino_file, line_number)
+    }
+
+    /// Shows a file in the preview, scrolled to `line_number`, starting at 1.
+    fn preview_file_at_line(
+        &mut self,
+        tino_file: &str,
+        line_number: usize,
+    ) -> Result<(), TinoError> {
+        let content = Self::read_preview(tino_file, self.config_file.preview.max_bytes)?;
+        self.preview_file(tino_file, content);
+        self.scroll_position = (self.preview_line(line_number - 1) as u16, 0);
         Ok(())
     }
 
+    /// Collects the open todo items again, keeping the selected row.
+    fn load_agenda(&mut self) {
+        self.agenda_items = collect_agenda(&self.tino_files, self.config_file.timezone().today());
+
+        self.agenda_rows = vec![];
+        let mut current_group = None;
+        for (index, agenda_item) in self.agenda_items.iter().enumerate() {
+            if current_group != Some(agenda_item.group) {
+                let group_size = self
+                    .agenda_items
+                    .iter()
+                    .filter(|other_item| other_item.group == agenda_item.group)
+                    .count();
+                self.agenda_rows
+                    .push(AgendaRow::Header(agenda_item.group, group_size));
+                current_group = Some(agenda_item.group);
+            }
+            self.agenda_rows.push(AgendaRow::Item(index));
+        }
+
+        // NOTE: The first row is a header, so the selection moves to the next item.
+        let selected_row = self
+            .agenda_state
+            .selected()
+            .unwrap_or(0)
+            .min(self.agenda_rows.len().saturating_sub(1));
+        self.agenda_state.select(
+            (selected_row..self.agenda_rows.len())
+                .chain((0..selected_row).rev())
+                .find(|i| matches!(self.agenda_rows[*i], AgendaRow::Item(_))),
+        );
+    }
+
+    fn selected_agenda_item(&self) -> Option<&AgendaItem> {
+        match self
+            .agenda_state
+            .selected()
+            .and_then(|i| self.agenda_rows.get(i))
+        {
+            Some(AgendaRow::Item(index)) => self.agenda_items.get(*index),
+            _ => None,
+        }
+    }
+
+    /// Selects the next item of the agenda, group headers are skipped.
+    fn agenda_next(&mut self) {
+        if self.agenda_items.is_empty() {
+            return;
+        }
+        let mut i = self.agenda_state.selected().unwrap_or(0);
+        loop {
+            i = if i >= self.agenda_rows.len() - 1 {
+                0
+            } else {
+                i + 1
+            };
+            if matches!(self.agenda_rows[i], AgendaRow::Item(_)) {
+                break;
+            }
+        }
+        self.agenda_state.select(Some(i));
+    }
+
+    /// Selects the previous item of the agenda, group headers are skipped.
+    fn agenda_previous(&mut self) {
+        if self.agenda_items.is_empty() {
+            return;
+        }
+        let mut i = self.agenda_state.selected().unwrap_or(0);
+        loop {
+            i = if i == 0 {
+                self.agenda_rows.len() - 1
+            } else {
+                i - 1
+            };
+            if matches!(self.agenda_rows[i], AgendaRow::Item(_)) {
+                break;
+            }
+        }
+        self.agenda_state.select(Some(i));
+    }
+
+    /// Shows the file of the selected agenda item in the preview, scrolled to the item.
+    fn preview_agenda_item(&mut self) -> Result<(), TinoError> {
+        let Some(agenda_item) = self.selected_agenda_item() else {
+            return Ok(());
+        };
+        let line_number = agenda_item.todo_item.line_index + 1;
+        let tino_file = agenda_item.tino_file.path.clone();
+        self.preview_file_at_line(&tino_file, line_number)
+    }
+
+    /// Reads the previewed file again, keeping the scroll position.
+    fn refresh_preview(&mut self) -> Result<(), TinoError> {
+        let Some(previewed_file) = &self.previewed_file else {
+            return Ok(());
+        };
+        match Self::read_preview(previewed_file, self.config_file.preview.max_bytes) {
+            Ok(content) => {
+                self.file_to_preview = content;
+                Ok(())
+            }
+            Err(error) => {
+                self.previewed_file = None;
+                Err(error)
+            }
+        }
+    }
+
+    /// Row of the preview, after wrapping, where the line `line_index` of the previewed file
+    /// starts.
+    fn preview_line(&mut self, line_index: usize) -> usize {
+        let (width, raw) = (self.preview_width, self.raw_preview);
+        let rendered_markdown = self.rendered_preview();
+        let rendered_line = rendered_markdown.rendered_line(line_index);
+        wrapped_row(&rendered_markdown.text, rendered_line, width, raw)
+    }
+
+    /// The previewed file rendered for the preview width, or as it is in the raw preview.
+    ///
+    /// It's rendered again only when the content, the width or the raw preview change,
+    /// highlighting code blocks is too slow to do it on each frame.
+    fn rendered_preview(&mut self) -> &RenderedMarkdown {
+        let rendered_preview = match self.rendered_preview.take() {
+            Some(rendered_preview)
+                if rendered_preview.width == self.preview_width
+                    && rendered_preview.raw == self.raw_preview
+                    && rendered_preview.content == self.file_to_preview =>
+            {
+                rendered_preview
+            }
+            _ => RenderedPreview {
+                content: self.file_to_preview.clone(),
+                width: self.preview_width,
+                raw: self.raw_preview,
+                rendered_markdown: if self.raw_preview {
+                    RenderedMarkdown::raw(&self.file_to_preview)
+                } else {
+                    render_markdown(&self.file_to_preview, self.preview_width as usize)
+                },
+            },
+        };
+        &self
+            .rendered_preview
+            .insert(rendered_preview)
+            .rendered_markdown
+    }
+
+    /// Checkboxes of the previewed file, if it's a todo file.
+    fn previewed_checkboxes(&self) -> Vec<Checkbox> {
+        let is_todo = self.previewed_file.as_ref().is_some_and(|previewed_file| {
+            self.tino_files
+                .iter()
+                .any(|tino_file| &tino_file.path == previewed_file && tino_file.progress.is_some())
+        });
+        if is_todo {
+            parse_checkboxes(&self.file_to_preview)
+        } else {
+            vec![]
+        }
+    }
+
+    fn checkbox_next(&mut self) {
+        let checkboxes = self.previewed_checkboxes();
+        if checkboxes.is_empty() {
+            return;
+        }
+        let i = match self.checkbox_cursor {
+            Some(i) => {
+                if i >= checkboxes.len() - 1 {
+                    0
+                } else {
+                    i + 1
+                }
+            }
+            None => 0,
+        };
+        self.checkbox_cursor = Some(i);
+        self.scroll_to_checkbox();
+    }
+
+    fn checkbox_previous(&mut self) {
+        let checkboxes = self.previewed_checkboxes();
+        if checkboxes.is_empty() {
+            return;
+        }
+        let i = match self.checkbox_cursor {
+            Some(i) => {
+                if i == 0 {
+                    checkboxes.len() - 1
+                } else {
+                    i - 1
+                }
+            }
+            None => checkboxes.len() - 1,
+        };
+        self.checkbox_cursor = Some(i);
+        self.scroll_to_checkbox();
+    }
+
+    /// Scrolls the preview until the selected checkbox is shown.
+    fn scroll_to_checkbox(&mut self) {
+        let Some(checkbox) = self
+            .checkbox_cursor
+            .and_then(|i| self.previewed_checkboxes().get(i).copied())
+        else {
+            return;
+        };
+        let line = self.preview_line(checkbox.line_index) as u16;
+        if line < self.scroll_position.0 {
+            self.scroll_position.0 = line;
+        } else if line >= self.scroll_position.0 + self.preview_height {
+            self.scroll_position.0 = line + 1 - self.preview_height.max(1);
+        }
+    }
+
+    /// Toggles the selected checkbox in the previewed file and writes it.
+    ///
+    /// The file is read again, so changes made since it was previewed are kept.
+    fn toggle_selected_checkbox(&mut self) -> Result<(), TinoError> {
+        let (Some(previewed_file), Some(checkbox)) = (
+            self.previewed_file.clone(),
+            self.checkbox_cursor
+                .and_then(|i| self.previewed_checkboxes().get(i).copied()),
+        ) else {
+            return Ok(());
+        };
+
+        let content = fs::read_to_string(&previewed_file).map_err(TinoError::ReadTinoFileFailed)?;
+        // NOTE: The file could have changed since it was previewed, the line is toggled
+        // only if it's still the one shown.
+        let checkbox_on_disk = parse_checkboxes(&content)
+            .into_iter()
+            .find(|checkbox_on_disk| *checkbox_on_disk == checkbox)
+            .filter(|_| {
+                content.lines().nth(checkbox.line_index)
+                    == self.file_to_preview.lines().nth(checkbox.line_index)
+            });
+        let (Some(checkbox), Some(mut toggled_content)) = (
+            checkbox_on_disk,
+            toggle_checkbox(&content, checkbox.line_index),
+        ) else {
+            self.refresh_preview()?;
+            return Err(TinoError::CheckboxNotFound(checkbox.line_index + 1));
+        };
+        let mut next_due = None;
+        if !checkbox.checked
+            && let Some((recurred_content, due)) = add_next_recurrence(
+                &toggled_content,
+                checkbox.line_index,
+                self.config_file.timezone().today(),
+            )
+        {
+            toggled_content = recurred_content;
+            next_due = Some(due);
+        }
+        write_atomically(&previewed_file, &toggled_content)?;
+
+        if let Some(next_due) = next_due {
+            self.report_info(format!(
+                "Added the next recurrence, due {}",
+                next_due.format("%Y-%m-%d")
+            ));
+        }
+
+        self.reload_tino_files();
+        self.refresh_preview()
+    }
+
+    /// File, and line, to open in the editor, the search match if search results are shown
+    /// or the agenda item if the agenda is shown.
+    fn editor_target(&self) -> Option<(String, Option<usize>)> {
+        if self.show_search_results {
+            self.selected_search_match().map(|search_match| {
+                (
+                    search_match.tino_file.path.clone(),
+                    Some(search_match.line_number),
+                )
+            })
+        } else if self.show_agenda {
+            self.selected_agenda_item().map(|agenda_item| {
+                (
+                    agenda_item.tino_file.path.clone(),
+                    Some(agenda_item.todo_item.line_index + 1),
+                )
+            })
+        } else {
+            self.selected_tino_file()
+                .map(|tino_file| (tino_file.to_string(), None))
+        }
+    }
+
+    /// Creates a file in `tino_dir` with the title in the File name element.
+    ///
+    /// Characters that can't be in file names are replaced, and a counter is added to the name
+    /// if it's taken, existing files are never overwritten.
+    fn create_tino_file(&mut self, tino_dir: &str) -> anyhow::Result<CreatedTinoFile> {
+        let title = self.file_name_input.value().trim().to_string();
+        let category = self
+            .selected_category()
+            .filter(|category| !category.is_none())
+            .map(|category| category.name.clone());
+        let timestamp = self.config_file.timezone().now();
+        let file_name = self.generate_file_name(timestamp)?;
+
+        // NOTE: The frontmatter keeps the values of the file name if the file is renamed.
+        let frontmatter = Frontmatter {
+            title: Some(title.clone()).filter(|title| !title.is_empty()),
+            tino_type: self
+                .selected_tino_type()
+                .map(|tino_type| tino_type.name.clone()),
+            category: category.clone(),
+            created: Some(timestamp.to_rfc3339_opts(SecondsFormat::Secs, false)),
+            tags: vec![],
+            status: Some(String::from("open")),
+        };
+
+        let template = match self.selected_tino_type() {
+            Some(tino_type) => self.config_file.read_template(tino_type)?,
+            None => None,
+        };
+        let content = match template {
+            Some(template) => {
+                let template = fill_template(
+                    &template,
+                    &[
+                        ("title", &title),
+                        ("date", &timestamp.format("%Y-%m-%d").to_string()),
+                        ("time", &timestamp.format("%H:%M").to_string()),
+                        (
+                            "created",
+                            &timestamp.to_rfc3339_opts(SecondsFormat::Secs, false),
+                        ),
+                        ("category", category.as_deref().unwrap_or_default()),
+                        ("type", frontmatter.tino_type.as_deref().unwrap_or_default()),
+                    ],
+                );
+                // NOTE: Templates with their own frontmatter replace the default one.
+                if Frontmatter::starts(&template) {
+                    template
+                } else {
+                    frontmatter.to_markdown()? + &template
+                }
+            }
+            None => frontmatter.to_markdown()?,
+        };
+
+        let tino_dir = tino_dir.into_path()?.canonicalize()?;
+        let bufpath = create_new_file(&tino_dir, &file_name, &content)?;
+
+        let mut notes = vec![];
+        let sanitized_title = sanitize(&title);
+        if sanitized_title != title {
+            notes.push(format!(
+                "The title had characters that can't be in file names, \"{}\" was used instead.",
+                sanitized_title
+            ));
+        }
+        if bufpath != tino_dir.join(&file_name) {
+            notes.push(format!(
+                "{} already existed, a number was added to the new file name.",
+                file_name
+            ));
+        }
+
+        self.reload_tino_files();
+        self.select_tino_file(&bufpath.display().to_string());
+        Ok(CreatedTinoFile {
+            path: bufpath,
+            notes,
+        })
+    }
+
+    /// Reads the TINO files of a directory. Entries that can't be read, and frontmatters that
+    /// can't be parsed, are returned as errors next to the files. Hidden files and
+    /// subdirectories are skipped.
     fn get_tino_dir_files(
-        tino_dir: String,
-        tino_file_type: TinoFileTypes,
-    ) -> anyhow::Result<Vec<(String, String)>> {
-        let tino_todo_dir = fs::read_dir(tino_dir.into_path()?.canonicalize()?)?;
+        tino_type: &TinoType,
+        config_file: &ConfigFile,
+    ) -> anyhow::Result<(Vec<TinoFile>, Vec<anyhow::Error>)> {
+        let tino_todo_dir = fs::read_dir(tino_type.dir.as_str().into_path()?.canonicalize()?)?;
         let mut tino_files = vec![];
+        let mut errors = vec![];
         for tino_file in tino_todo_dir {
-            let tino_file = tino_file?;
+            let tino_file = match tino_file {
+                Ok(tino_file) => tino_file,
+                Err(error) => {
+                    errors.push(anyhow::Error::from(error).context(format!(
+                        "Can't read an entry of the {} directory {}",
+                        tino_type.name, tino_type.dir
+                    )));
+                    continue;
+                }
+            };
+            let file_name = tino_file.file_name().to_string_lossy().to_string();
+            // NOTE: Hidden files include the temporary files of `write_atomically`.
+            if file_name.starts_with('.') {
+                continue;
+            }
 
-            tino_files.push((
-                Self::format_tino_file(tino_file_type, tino_file.file_name()),
-                tino_file.path().canonicalize()?.display().to_string(),
-            ));
+            let path = tino_file.path();
+            let (metadata, path) = match fs::metadata(&path)
+                .and_then(|metadata| Ok((metadata, path.canonicalize()?)))
+            {
+                Ok(metadata_and_path) => metadata_and_path,
+                Err(error) => {
+                    errors.push(
+                        anyhow::Error::from(error)
+                            .context(format!("Can't read {}", path.display())),
+                    );
+                    continue;
+                }
+            };
+            if metadata.is_dir() {
+                continue;
+            }
+
+            let tino_file = TinoFile::new(
+                tino_type,
+                file_name,
+                path.display().to_string(),
+                metadata.modified().ok(),
+                config_file,
+            );
+            if let Some(frontmatter_error) = &tino_file.frontmatter_error {
+                errors.push(anyhow::anyhow!("{}: {}", tino_file.path, frontmatter_error));
+            }
+            tino_files.push(tino_file);
         }
-        Ok(tino_files)
+        Ok((tino_files, errors))
     }
 }
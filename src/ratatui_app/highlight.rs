@@ -0,0 +1,108 @@
+use std::{str::FromStr, sync::LazyLock};
+
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+use syntect::{
+    easy::HighlightLines,
+    highlighting::{self, FontStyle, ScopeSelectors, StyleModifier, Theme, ThemeItem},
+    parsing::SyntaxSet,
+    util::LinesWithEndings,
+};
+
+static SYNTAX_SET: LazyLock<SyntaxSet> = LazyLock::new(SyntaxSet::load_defaults_newlines);
+static THEME: LazyLock<Theme> = LazyLock::new(ansi_theme);
+
+/// Colors of the theme, as ANSI color indices so code uses the terminal palette like the rest
+/// of the TUI.
+const THEME_COLORS: [(&str, u8); 7] = [
+    ("comment", 8),
+    ("string, constant.character", 2),
+    ("constant.numeric, constant.language", 3),
+    ("keyword, storage", 5),
+    ("entity.name.function, support.function", 4),
+    (
+        "entity.name.type, entity.name.class, support.type, support.class",
+        6,
+    ),
+    ("variable.parameter, entity.name.tag", 1),
+];
+
+/// Theme whose colors are ANSI color indices, the index is stored in the red channel
+/// and alpha is 0.
+fn ansi_theme() -> Theme {
+    let mut theme = Theme::default();
+    theme.settings.foreground = Some(ansi_color(7));
+    theme.scopes = THEME_COLORS
+        .iter()
+        .filter_map(|(scope, color)| {
+            Some(ThemeItem {
+                scope: ScopeSelectors::from_str(scope).ok()?,
+                style: StyleModifier {
+                    foreground: Some(ansi_color(*color)),
+                    background: None,
+                    font_style: None,
+                },
+            })
+        })
+        .collect();
+    theme
+}
+
+fn ansi_color(index: u8) -> highlighting::Color {
+    highlighting::Color {
+        r: index,
+        g: 0,
+        b: 0,
+        a: 0,
+    }
+}
+
+/// Highlights `code` written in `language`, a name or file extension like `rust` or `rs`.
+///
+/// Returns `None` if the language isn't known.
+pub fn highlight_code(language: &str, code: &str) -> Option<Vec<Line<'static>>> {
+    if language.is_empty() {
+        return None;
+    }
+    let syntax = SYNTAX_SET.find_syntax_by_token(language)?;
+    let mut highlighter = HighlightLines::new(syntax, &THEME);
+
+    let mut lines = vec![];
+    for line in LinesWithEndings::from(code) {
+        let ranges = highlighter.highlight_line(line, &SYNTAX_SET).ok()?;
+        let mut spans: Vec<Span> = ranges
+            .into_iter()
+            .map(|(style, text)| {
+                Span::styled(
+                    text.trim_end_matches(['\n', '\r']).to_string(),
+                    span_style(style),
+                )
+            })
+            .filter(|span| !span.content.is_empty())
+            .collect();
+        // NOTE: Blank lines keep an empty span, lines without spans aren't shown.
+        if spans.is_empty() {
+            spans.push(Span::raw(""));
+        }
+        lines.push(Line::from(spans));
+    }
+    Some(lines)
+}
+
+fn span_style(style: highlighting::Style) -> Style {
+    let foreground = style.foreground;
+    let mut span_style = Style::default().fg(if foreground.a == 0 {
+        Color::Indexed(foreground.r)
+    } else {
+        Color::Rgb(foreground.r, foreground.g, foreground.b)
+    });
+    if style.font_style.contains(FontStyle::BOLD) {
+        span_style = span_style.add_modifier(Modifier::BOLD);
+    }
+    if style.font_style.contains(FontStyle::ITALIC) {
+        span_style = span_style.add_modifier(Modifier::ITALIC);
+    }
+    span_style
+}
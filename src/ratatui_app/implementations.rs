@@ -1,23 +1,38 @@
-use std::env;
-use std::process::Command;
-
-use crate::app::config_file::ConfigFile;
-use crate::app::utils::TinoError;
-use crate::ratatui_app::{helper_methods::Helpers, types::App};
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
-use ratatui::layout::Alignment;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::Duration;
+
+use crate::app::agenda::AgendaGroup;
+use crate::app::checklist::{Priority, TodoFilter};
+use crate::app::config_file::{Category, ConfigFile};
+use crate::app::search::SearchMode;
+use crate::app::tino_file::TIMESTAMP_FORMAT;
+use crate::app::utils::{TinoError, open_editor};
+use crate::ratatui_app::{
+    events::{AppEvent, Events, JobResult},
+    helper_methods::Helpers,
+    types::{AgendaRow, App, TinoFilesRow},
+};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Alignment, Rect};
 use ratatui::prelude::{Constraint, Direction, Layout};
 use ratatui::style::Stylize;
-use ratatui::text::{Line, Text};
+use ratatui::text::{Line, Span, Text};
 use ratatui::widgets::Wrap;
 use ratatui::{
     DefaultTerminal, Frame,
     style::{Color, Style},
-    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
 };
 use tui_input::Input;
 use tui_input::backend::crossterm::EventHandler;
 
+/// How long a status message is shown before the next key press clears it.
+const STATUS_MESSAGE_DURATION: Duration = Duration::from_secs(5);
+/// Time without key presses before the selected TINO file is previewed.
+const PREVIEW_DELAY: Duration = Duration::from_millis(150);
+/// Time without changes in the TINO directories before the TINO files are reloaded.
+const FS_CHANGES_DELAY: Duration = Duration::from_millis(100);
+
 impl App {
     /// Construct a new instance of [`App`].
     pub fn new(config_file: ConfigFile) -> anyhow::Result<Self> {
@@ -30,55 +45,136 @@ impl App {
         let mut tino_files_state = ListState::default();
         tino_files_state.select(Some(0));
 
-        Ok(Self {
+        let mut app = Self {
             running: false,
+            needs_redraw: true,
             active_field: 0,
             open_editor: false,
             config_file: config_file.clone(),
             scroll_position: (0, 0),
             file_name_input: Input::default(),
-            type_items: vec![
-                "Todos".to_string(),
-                "Ideas".to_string(),
-                "Notes".to_string(),
-                "Academic notes".to_string(),
-            ],
+            type_items: config_file
+                .tino_types
+                .iter()
+                .map(|tino_type| tino_type.name.clone())
+                .collect(),
             type_state,
-            category_items: vec![
-                "".to_string(),
-                "Project".to_string(),
-                "Area".to_string(),
-                "Resource".to_string(),
-                "Archive".to_string(),
-            ],
+            category_items: std::iter::once(Category::none())
+                .chain(config_file.categories.items.iter().cloned())
+                .collect(),
             category_state,
-            tino_files: Self::get_tino_files(config_file.clone())?,
+            tino_files: vec![],
+            tino_files_rows: vec![],
+            collapsed_groups: HashSet::new(),
             tino_files_state,
+            filtering: false,
+            filter_input: Input::default(),
+            filter_matches: HashMap::new(),
+            todo_filter: TodoFilter::default(),
+            searching: false,
+            search_input: Input::default(),
+            search_mode: SearchMode::default(),
+            show_search_results: false,
+            search_results: vec![],
+            search_state: ListState::default(),
+            search_index: None,
+            show_agenda: false,
+            agenda_items: vec![],
+            agenda_rows: vec![],
+            agenda_state: ListState::default(),
             file_to_preview: String::from("File preview"),
-        })
+            previewed_file: None,
+            raw_preview: false,
+            checkbox_cursor: None,
+            preview_height: 0,
+            preview_width: 0,
+            rendered_preview: None,
+            preview_scroll_positions: HashMap::new(),
+            preview_requested_at: None,
+            events: Events::new(),
+            watcher: None,
+            fs_changed_at: None,
+            tino_files_generation: 0,
+            search_running: false,
+            queued_search: None,
+            status_message: None,
+            error_log: VecDeque::new(),
+            show_error_log: false,
+        };
+        app.update_tino_files_rows();
+        Ok(app)
     }
 
     /// Run the application's main loop.
+    ///
+    /// The TINO files are read in the background, so the first frames can show an empty list.
     pub fn run(mut self, mut terminal: DefaultTerminal) -> anyhow::Result<()> {
         self.running = true;
+        self.events.start();
+        self.watch_tino_dirs();
+        self.reload_tino_files_in_background();
         while self.running {
-            terminal.draw(|frame| self.render(frame))?;
-            self.handle_crossterm_events()?;
-        }
-        if self.open_editor {
-            let editor = env::var("EDITOR").unwrap_or_else(|_| "vim".to_string());
-
-            Command::new(editor)
-                .arg(match self.selected_tino_file() {
-                    Some(tino_file) => String::from(tino_file),
-                    None => return Err(TinoError::NotSelectedTinoFile.into()),
-                })
-                .status()
-                .expect("ERROR: while openning editor.");
+            // NOTE: Frames are only drawn after a change, rendering the preview can be slow.
+            if self.needs_redraw {
+                terminal.draw(|frame| self.render(frame))?;
+                self.needs_redraw = false;
+            }
+            match self.events.next()? {
+                AppEvent::Terminal(event) => {
+                    self.handle_crossterm_event(event?);
+                    self.needs_redraw = true;
+                }
+                AppEvent::Tick => self.needs_redraw |= self.on_tick(),
+                AppEvent::FsChanged(fs_event) => {
+                    // NOTE: Changes are shown when they are reloaded, only errors are shown now.
+                    self.needs_redraw |= fs_event.is_err();
+                    self.handle_fs_event(fs_event);
+                }
+                AppEvent::JobDone(job_result) => {
+                    self.on_job_done(job_result);
+                    self.needs_redraw = true;
+                }
+            }
+            if self.open_editor {
+                self.open_editor = false;
+                terminal = self.suspend_for_editor();
+                self.needs_redraw = true;
+            }
         }
         Ok(())
     }
 
+    /// Restores the terminal, runs $EDITOR and initializes the terminal again when it closes.
+    ///
+    /// The file list and preview are refreshed because the file could have been changed,
+    /// or renamed, in the editor.
+    fn suspend_for_editor(&mut self) -> DefaultTerminal {
+        let Some((tino_file, line)) = self.editor_target() else {
+            return ratatui::init();
+        };
+
+        // NOTE: The editor reads the terminal events while it's open.
+        self.events.pause_input();
+        ratatui::restore();
+        let editor_result = open_editor(&tino_file, line);
+        let terminal = ratatui::init();
+        self.events.resume_input();
+
+        self.reload_tino_files();
+        if let Err(error) = self.refresh_preview() {
+            self.report_error(error.into());
+        }
+
+        match editor_result {
+            Ok(status) if !status.success() => {
+                self.report_error(TinoError::EditorExitedWithError(status).into());
+            }
+            Err(error) => self.report_error(error.into()),
+            Ok(_) => {}
+        }
+        terminal
+    }
+
     /// Renders the user interface.
     ///
     /// This is where you add new widgets. See the following resources for more information:
@@ -168,7 +264,16 @@ impl App {
         let category_items: Vec<ListItem> = self
             .category_items
             .iter()
-            .map(|i| ListItem::new(i.as_str()))
+            .map(|i| {
+                let item = match &i.code {
+                    Some(code) => ListItem::new(format!("{} ({})", i.name, code)),
+                    None => ListItem::new(i.name.as_str()),
+                };
+                match i.color() {
+                    Some(color) => item.fg(color),
+                    None => item,
+                }
+            })
             .collect();
         let category_list = List::new(category_items)
             .block(
@@ -189,44 +294,123 @@ impl App {
             Style::default()
         };
         let tino_files_items: Vec<ListItem> = self
-            .tino_files
+            .tino_files_rows
             .iter()
-            .map(|i| ListItem::new(i.0.as_str()).white())
+            .map(|i| match i {
+                TinoFilesRow::Header(group, count) => {
+                    let marker = if self.collapsed_groups.contains(group) {
+                        "▸"
+                    } else {
+                        "▾"
+                    };
+                    ListItem::new(format!("{} {} ({})", marker, group, count))
+                        .bold()
+                        .yellow()
+                }
+                TinoFilesRow::File(index) => ListItem::new(self.tino_file_line(*index)).white(),
+            })
             .collect();
+        let tino_files_title = format!(
+            "TINO files (sort: {}{}{})",
+            self.config_file.list.sort.as_str(),
+            if self.config_file.list.group {
+                ", grouped"
+            } else {
+                ""
+            },
+            match self.todo_filter {
+                TodoFilter::All => String::new(),
+                todo_filter => format!(", {}", todo_filter.as_str()),
+            }
+        );
+        let mut tino_files_block = Block::new()
+            .borders(Borders::ALL)
+            .title(tino_files_title)
+            .style(tino_files_style)
+            .title_bottom(Line::from("(Ctrl+l)").alignment(Alignment::Right));
+        if self.filtering || !self.filter_input.value().is_empty() {
+            tino_files_block = tino_files_block.title_bottom(
+                Line::from(format!("/{}", self.filter_input.value()))
+                    .alignment(Alignment::Left)
+                    .white(),
+            );
+        }
         let tino_files_list = List::new(tino_files_items)
-            .block(
-                Block::new()
-                    .borders(Borders::ALL)
-                    .title("TINO files")
-                    .style(tino_files_style)
-                    .title_bottom(Line::from("(Ctrl+l)").alignment(Alignment::Right)),
-            )
+            .block(tino_files_block)
             .highlight_symbol(">> ")
             .highlight_style(Style::default().fg(Color::Cyan));
-        frame.render_stateful_widget(
-            tino_files_list,
-            files_list_and_preview_layout[1],
-            &mut self.tino_files_state,
-        );
+        if self.show_search_results {
+            self.render_search_results(frame, files_list_and_preview_layout[1]);
+        } else if self.show_agenda {
+            self.render_agenda(frame, files_list_and_preview_layout[1]);
+        } else {
+            frame.render_stateful_widget(
+                tino_files_list,
+                files_list_and_preview_layout[1],
+                &mut self.tino_files_state,
+            );
+        }
 
         let file_preview_style = if self.active_field == 4 {
             Style::default().fg(Color::Magenta)
         } else {
             Style::default()
         };
+        let file_preview_block = Block::new()
+            .borders(Borders::ALL)
+            .title_bottom(
+                Line::from(format!(
+                    "r: {} | a: auto {}",
+                    if self.raw_preview { "rendered" } else { "raw" },
+                    if self.config_file.preview.auto {
+                        "off"
+                    } else {
+                        "on"
+                    }
+                ))
+                .alignment(Alignment::Left)
+                .white(),
+            )
+            .title_bottom(Line::from("(Ctrl+p)").alignment(Alignment::Right));
+        self.preview_width = files_list_and_preview_layout[2].width.saturating_sub(2);
+        let selected_checkbox = self
+            .checkbox_cursor
+            .and_then(|i| self.previewed_checkboxes().get(i).copied());
+        let rendered_markdown = self.rendered_preview();
+        let selected_line =
+            selected_checkbox.map(|checkbox| rendered_markdown.rendered_line(checkbox.line_index));
+        let mut text = rendered_markdown.text.clone();
+        if let Some(selected_line) = selected_line {
+            highlight_preview_line(&mut text, selected_line);
+        }
+        let file_preview = if self.raw_preview {
+            Paragraph::new(text.bold().white()).wrap(Wrap { trim: true })
+        } else {
+            Paragraph::new(text.white()).wrap(Wrap { trim: false })
+        };
+        self.preview_height = files_list_and_preview_layout[2].height.saturating_sub(2);
         frame.render_widget(
-            Paragraph::new(Text::from(self.file_to_preview.clone()).bold().white())
-                .block(
-                    Block::new()
-                        .borders(Borders::ALL)
-                        .title_bottom(Line::from("(Ctrl+p)").alignment(Alignment::Right)),
-                )
+            file_preview
+                .block(file_preview_block)
                 .style(file_preview_style)
-                .wrap(Wrap { trim: true })
                 .scroll((self.scroll_position.0, 0)),
             files_list_and_preview_layout[2],
         );
 
+        self.render_status_bar(
+            frame,
+            Rect {
+                x: files_list_and_preview_layout[1].x,
+                y: main_layout[3].y,
+                width: files_list_and_preview_layout[1].width
+                    + files_list_and_preview_layout[2].width,
+                height: main_layout[3].height.min(1),
+            },
+        );
+        if self.show_error_log {
+            self.render_error_log(frame);
+        }
+
         let (cursor_x, cursor_y) = match self.active_field {
             0 => {
                 let input = &self.file_name_input;
@@ -235,28 +419,522 @@ impl App {
                     form_layout[1].y + 1,
                 )
             }
+            3 if self.searching => {
+                let area = files_list_and_preview_layout[1];
+                (
+                    area.x + self.search_input.visual_cursor() as u16 + 2,
+                    area.y + area.height.saturating_sub(1),
+                )
+            }
+            3 if self.filtering => {
+                let area = files_list_and_preview_layout[1];
+                (
+                    area.x + self.filter_input.visual_cursor() as u16 + 2,
+                    area.y + area.height.saturating_sub(1),
+                )
+            }
             _ => (0, 0),
         };
         frame.set_cursor_position((cursor_x, cursor_y));
     }
 
-    /// Reads the crossterm events and updates the state of [`App`].
+    /// Renders the last status message, and how to open the error log.
+    fn render_status_bar(&self, frame: &mut Frame, area: Rect) {
+        let status_line = match &self.status_message {
+            Some(status_message) if status_message.is_error => {
+                Line::from(status_message.text.clone()).red()
+            }
+            Some(status_message) => Line::from(status_message.text.clone()).white(),
+            None => Line::default(),
+        };
+        frame.render_widget(Paragraph::new(status_line), area);
+        if !self.error_log.is_empty() {
+            frame.render_widget(
+                Paragraph::new(
+                    Line::from(format!("Ctrl+e: errors ({})", self.error_log.len())).dark_gray(),
+                )
+                .alignment(Alignment::Right),
+                area,
+            );
+        }
+    }
+
+    /// Renders the error log in a popup over the rest of the user interface.
+    fn render_error_log(&self, frame: &mut Frame) {
+        let frame_area = frame.area();
+        let width = frame_area.width * 8 / 10;
+        let height = frame_area.height * 6 / 10;
+        let area = Rect {
+            x: frame_area.x + (frame_area.width - width) / 2,
+            y: frame_area.y + (frame_area.height - height) / 2,
+            width,
+            height,
+        };
+
+        let error_log_items: Vec<ListItem> = self
+            .error_log
+            .iter()
+            .map(|(time, message)| {
+                ListItem::new(Line::from(vec![
+                    Span::from(format!("{} ", time.format("%H:%M:%S"))).dark_gray(),
+                    Span::from(message.clone()).red(),
+                ]))
+            })
+            .collect();
+        frame.render_widget(Clear, area);
+        frame.render_widget(
+            List::new(error_log_items).block(
+                Block::new()
+                    .borders(Borders::ALL)
+                    .title("Errors")
+                    .title_bottom(Line::from("(Esc)").alignment(Alignment::Right)),
+            ),
+            area,
+        );
+    }
+
+    /// Renders the search results in place of the TINO files list.
+    fn render_search_results(&mut self, frame: &mut Frame, area: Rect) {
+        let search_results_style = if self.active_field == 3 {
+            Style::default().fg(Color::Magenta)
+        } else {
+            Style::default()
+        };
+        let search_results_items: Vec<ListItem> = self
+            .search_results
+            .iter()
+            .map(|i| {
+                let mut spans = vec![
+                    Span::from(format!("{} | {}", i.tino_file.label, i.tino_file.file_name)),
+                    Span::from(format!(":{}  ", i.line_number)).dark_gray(),
+                ];
+                let mut last_end = 0;
+                for (start, end) in i.ranges.iter() {
+                    spans.push(Span::from(i.line[last_end..*start].to_string()));
+                    spans.push(Span::from(i.line[*start..*end].to_string()).yellow().bold());
+                    last_end = *end;
+                }
+                spans.push(Span::from(i.line[last_end..].to_string()));
+                ListItem::new(Line::from(spans)).white()
+            })
+            .collect();
+
+        let search_results_title = if self.search_running {
+            format!("Search ({}): searching...", self.search_mode.as_str())
+        } else {
+            format!(
+                "Search ({}): {} result{}",
+                self.search_mode.as_str(),
+                self.search_results.len(),
+                if self.search_results.len() == 1 {
+                    ""
+                } else {
+                    "s"
+                }
+            )
+        };
+        let search_results_list = List::new(search_results_items)
+            .block(
+                Block::new()
+                    .borders(Borders::ALL)
+                    .title(search_results_title)
+                    .style(search_results_style)
+                    .title_bottom(
+                        Line::from(format!("?{}", self.search_input.value()))
+                            .alignment(Alignment::Left)
+                            .white(),
+                    )
+                    .title_bottom(Line::from("(Ctrl+f)").alignment(Alignment::Right)),
+            )
+            .highlight_symbol(">> ")
+            .highlight_style(Style::default().fg(Color::Cyan));
+        frame.render_stateful_widget(search_results_list, area, &mut self.search_state);
+    }
+
+    /// Renders the agenda in place of the TINO files list.
+    fn render_agenda(&mut self, frame: &mut Frame, area: Rect) {
+        let agenda_style = if self.active_field == 3 {
+            Style::default().fg(Color::Magenta)
+        } else {
+            Style::default()
+        };
+        let agenda_items: Vec<ListItem> = self
+            .agenda_rows
+            .iter()
+            .map(|agenda_row| match agenda_row {
+                AgendaRow::Header(group, group_size) => {
+                    ListItem::new(format!("{} ({})", group.as_str(), group_size))
+                        .yellow()
+                        .bold()
+                }
+                AgendaRow::Item(index) => {
+                    let agenda_item = &self.agenda_items[*index];
+                    let todo_item = &agenda_item.todo_item;
+                    let mut spans = vec![Span::from("  ")];
+                    if let Some(due) = todo_item.due {
+                        let due_span = Span::from(format!("{} ", due.format("%Y-%m-%d")));
+                        spans.push(if agenda_item.group == AgendaGroup::Overdue {
+                            due_span.red()
+                        } else {
+                            due_span
+                        });
+                    }
+                    if let Some(priority) = todo_item.priority {
+                        let priority_span = Span::from(format!("!{} ", priority.as_str()));
+                        spans.push(match priority {
+                            Priority::High => priority_span.red(),
+                            Priority::Medium => priority_span.yellow(),
+                            Priority::Low => priority_span.blue(),
+                        });
+                    }
+                    spans.push(Span::from(todo_item.text.clone()));
+                    for context in &todo_item.contexts {
+                        spans.push(Span::from(format!(" @{}", context)).cyan());
+                    }
+                    if let Some(recurrence) = todo_item.recurrence {
+                        spans.push(Span::from(format!(" {}", recurrence)).magenta());
+                    }
+                    spans.push(
+                        Span::from(format!(
+                            "  {} | {}:{}",
+                            agenda_item.tino_file.label,
+                            agenda_item.tino_file.file_name,
+                            todo_item.line_index + 1
+                        ))
+                        .dark_gray(),
+                    );
+                    ListItem::new(Line::from(spans)).white()
+                }
+            })
+            .collect();
+
+        let agenda_list = List::new(agenda_items)
+            .block(
+                Block::new()
+                    .borders(Borders::ALL)
+                    .title(format!(
+                        "Agenda (today: {})",
+                        self.config_file.timezone().today().format("%Y-%m-%d")
+                    ))
+                    .style(agenda_style)
+                    .title_bottom(Line::from("(Ctrl+a)").alignment(Alignment::Right)),
+            )
+            .highlight_symbol(">> ")
+            .highlight_style(Style::default().fg(Color::Cyan));
+        frame.render_stateful_widget(agenda_list, area, &mut self.agenda_state);
+    }
+
+    /// Builds the line of a TINO file in the TINO files list, a column for each field.
+    fn tino_file_line(&self, index: usize) -> Line<'static> {
+        let tino_file = &self.tino_files[index];
+        let mut spans = vec![Span::from(format!("{} | ", tino_file.label))];
+
+        match tino_file.timestamp {
+            Some(timestamp) => {
+                if !tino_file.title.is_empty() {
+                    spans.extend(self.filter_highlighted_spans(index, &tino_file.title));
+                    spans.push(Span::from(" "));
+                }
+                spans.push(Span::from(timestamp.format(TIMESTAMP_FORMAT).to_string()).dark_gray());
+            }
+            // NOTE: The title is the start of the file name, so matched characters are the same.
+            None => spans.extend(self.filter_highlighted_spans(index, &tino_file.file_name)),
+        }
+
+        if let Some(category) = &tino_file.category {
+            let category_color = self
+                .config_file
+                .find_category(category)
+                .ok()
+                .and_then(|category| category.color());
+            let category_span = Span::from(format!(" - {}", category));
+            spans.push(match category_color {
+                Some(color) => category_span.fg(color),
+                None => category_span,
+            });
+        }
+
+        for tag in &tino_file.tags {
+            spans.push(Span::from(format!(" #{}", tag)).cyan());
+        }
+
+        if let Some(progress) = tino_file.progress
+            && progress.total > 0
+        {
+            spans.push(Span::from("  "));
+            spans.push(if progress.is_done() {
+                Span::from(format!("✓ {}", progress)).green()
+            } else {
+                Span::from(progress.to_string()).yellow()
+            });
+        }
+
+        Line::from(spans)
+    }
+
+    /// Splits `text` in spans, highlighting the characters matched by the filter.
+    fn filter_highlighted_spans(&self, index: usize, text: &str) -> Vec<Span<'static>> {
+        let Some(indices) = self.filter_matches.get(&index) else {
+            return vec![Span::from(text.to_string())];
+        };
+
+        let mut spans: Vec<Span> = vec![];
+        let mut current = String::new();
+        let mut current_matched = false;
+        for (char_index, character) in text.chars().enumerate() {
+            let matched = indices.contains(&char_index);
+            if matched != current_matched && !current.is_empty() {
+                spans.push(Self::filter_span(
+                    std::mem::take(&mut current),
+                    current_matched,
+                ));
+            }
+            current_matched = matched;
+            current.push(character);
+        }
+        if !current.is_empty() {
+            spans.push(Self::filter_span(current, current_matched));
+        }
+        spans
+    }
+
+    fn filter_span(text: String, matched: bool) -> Span<'static> {
+        if matched {
+            Span::from(text).yellow().bold().underlined()
+        } else {
+            Span::from(text)
+        }
+    }
+
+    /// Handles the key events while the user types in the filter of the TINO files list.
+    fn on_filter_key_event(&mut self, key: KeyEvent) -> anyhow::Result<()> {
+        match key.code {
+            KeyCode::Esc => {
+                self.filtering = false;
+                self.filter_input.reset();
+                self.update_tino_files_rows();
+            }
+            KeyCode::Enter => self.filtering = false,
+            KeyCode::Down => self.tino_file_next(),
+            KeyCode::Up => self.tino_file_previous(),
+            _ => {
+                if self.filter_input.handle_event(&Event::Key(key)).is_some() {
+                    self.update_tino_files_rows();
+                    self.request_preview();
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Handles the key events while the user types in the search input.
+    fn on_search_key_event(&mut self, key: KeyEvent) -> anyhow::Result<()> {
+        match (key.modifiers, key.code) {
+            (_, KeyCode::Esc) => {
+                self.searching = false;
+                if self.search_results.is_empty() {
+                    self.show_search_results = false;
+                }
+            }
+            (_, KeyCode::Enter) => {
+                self.searching = false;
+                self.run_search();
+            }
+            (KeyModifiers::CONTROL, KeyCode::Char('r')) => {
+                self.search_mode = self.search_mode.next();
+            }
+            _ => {
+                self.search_input.handle_event(&Event::Key(key));
+            }
+        }
+        Ok(())
+    }
+
+    /// Updates the state of [`App`] with a crossterm event.
     ///
-    /// If your application needs to perform work in between handling events, you can use the
-    /// [`event::poll`] function to check if there are any events available with a timeout.
-    fn handle_crossterm_events(&mut self) -> anyhow::Result<()> {
-        match event::read()? {
+    /// Errors of the key events are reported in the status bar instead of closing the app.
+    fn handle_crossterm_event(&mut self, event: Event) {
+        match event {
             // it's important to check KeyEventKind::Press to avoid handling key release events
-            Event::Key(key) if key.kind == KeyEventKind::Press => self.on_key_event(key),
-            Event::Mouse(_) => Ok(()),
-            Event::Resize(_, _) => Ok(()),
-            _ => Ok(()),
+            Event::Key(key) if key.kind == KeyEventKind::Press => {
+                if let Err(error) = self.on_key_event(key) {
+                    self.report_error(error);
+                }
+            }
+            Event::Mouse(_) => {}
+            Event::Resize(_, _) => {}
+            _ => {}
+        }
+    }
+
+    /// Does the work that waits for some time to pass.
+    ///
+    /// Returns `true` if something shown changed.
+    fn on_tick(&mut self) -> bool {
+        let mut changed = false;
+        if self.status_message.as_ref().is_some_and(|status_message| {
+            status_message.shown_at.elapsed() > STATUS_MESSAGE_DURATION
+        }) {
+            self.status_message = None;
+            changed = true;
+        }
+
+        if self
+            .preview_requested_at
+            .is_some_and(|preview_requested_at| preview_requested_at.elapsed() >= PREVIEW_DELAY)
+        {
+            if let Err(error) = self.preview_selected_tino_file() {
+                self.report_error(error.into());
+            }
+            changed = true;
+        }
+
+        // NOTE: Changes come in bursts, like an editor saving a file, so they are reloaded once.
+        if self
+            .fs_changed_at
+            .is_some_and(|fs_changed_at| fs_changed_at.elapsed() >= FS_CHANGES_DELAY)
+        {
+            self.fs_changed_at = None;
+            self.reload_tino_files_in_background();
+        }
+        changed
+    }
+
+    fn on_job_done(&mut self, job_result: JobResult) {
+        match job_result {
+            JobResult::TinoFilesLoaded {
+                generation,
+                tino_files,
+                errors,
+            } => {
+                if generation != self.tino_files_generation {
+                    return;
+                }
+                for error in errors {
+                    self.report_error(error);
+                }
+                self.set_tino_files(tino_files);
+                if let Err(error) = self.refresh_preview() {
+                    self.report_error(error.into());
+                }
+                self.request_preview();
+            }
+            JobResult::SearchDone {
+                search_index,
+                search_results,
+            } => {
+                self.search_running = false;
+                self.search_index = search_index;
+                if let Err(error) = search_results
+                    .and_then(|search_results| self.set_search_results(search_results))
+                {
+                    self.report_error(error.into());
+                }
+                if let Some(query) = self.queued_search.take() {
+                    self.start_search(query);
+                }
+            }
         }
     }
 
     /// Handles the key events and updates the state of [`App`].
     fn on_key_event(&mut self, key: KeyEvent) -> anyhow::Result<()> {
+        if self.show_error_log {
+            if matches!(key.code, KeyCode::Esc | KeyCode::Char('q'))
+                || (key.modifiers, key.code) == (KeyModifiers::CONTROL, KeyCode::Char('e'))
+            {
+                self.show_error_log = false;
+            }
+            return Ok(());
+        }
+        if self.filtering {
+            return self.on_filter_key_event(key);
+        }
+        if self.searching {
+            return self.on_search_key_event(key);
+        }
+
         match (key.modifiers, key.code) {
+            (KeyModifiers::CONTROL, KeyCode::Char('e')) => {
+                self.show_error_log = true;
+                Ok(())
+            }
+            (KeyModifiers::CONTROL, KeyCode::Char('a')) => {
+                self.show_agenda = !self.show_agenda;
+                if self.show_agenda {
+                    self.active_field = 3;
+                    self.show_search_results = false;
+                    self.load_agenda();
+                    self.preview_agenda_item()?;
+                }
+                Ok(())
+            }
+            (KeyModifiers::CONTROL, KeyCode::Char('f')) => {
+                self.active_field = 3;
+                self.show_agenda = false;
+                self.show_search_results = true;
+                self.searching = true;
+                Ok(())
+            }
+            (_, KeyCode::Esc) if self.active_field == 3 && self.show_search_results => {
+                self.show_search_results = false;
+                Ok(())
+            }
+            (_, KeyCode::Down | KeyCode::Char('j'))
+                if self.active_field == 3 && self.show_search_results =>
+            {
+                self.search_next();
+                self.preview_search_match()?;
+                Ok(())
+            }
+            (_, KeyCode::Up | KeyCode::Char('k'))
+                if self.active_field == 3 && self.show_search_results =>
+            {
+                self.search_previous();
+                self.preview_search_match()?;
+                Ok(())
+            }
+            (_, KeyCode::Enter) if self.active_field == 3 && self.show_search_results => {
+                if self.selected_search_match().is_some() {
+                    self.open_editor = true;
+                }
+                Ok(())
+            }
+            (_, KeyCode::Char(_)) if self.active_field == 3 && self.show_search_results => Ok(()),
+            (_, KeyCode::Esc) if self.active_field == 3 && self.show_agenda => {
+                self.show_agenda = false;
+                Ok(())
+            }
+            (_, KeyCode::Down | KeyCode::Char('j'))
+                if self.active_field == 3 && self.show_agenda =>
+            {
+                self.agenda_next();
+                self.preview_agenda_item()?;
+                Ok(())
+            }
+            (_, KeyCode::Up | KeyCode::Char('k')) if self.active_field == 3 && self.show_agenda => {
+                self.agenda_previous();
+                self.preview_agenda_item()?;
+                Ok(())
+            }
+            (_, KeyCode::Enter) if self.active_field == 3 && self.show_agenda => {
+                if self.selected_agenda_item().is_some() {
+                    self.open_editor = true;
+                }
+                Ok(())
+            }
+            (_, KeyCode::Char(_)) if self.active_field == 3 && self.show_agenda => Ok(()),
+            (_, KeyCode::Esc)
+                if self.active_field == 3 && !self.filter_input.value().is_empty() =>
+            {
+                self.filter_input.reset();
+                self.update_tino_files_rows();
+                Ok(())
+            }
+            (_, KeyCode::Char('/')) if self.active_field == 3 => {
+                self.filtering = true;
+                Ok(())
+            }
             (_, KeyCode::Esc) => {
                 self.quit();
                 Ok(())
@@ -323,6 +1001,22 @@ impl App {
                     _ => Ok(()),
                 }
             }
+            (_, KeyCode::Char('r')) if self.active_field == 3 || self.active_field == 4 => {
+                self.raw_preview = !self.raw_preview;
+                Ok(())
+            }
+            (_, KeyCode::Char('J')) if self.active_field == 4 => {
+                self.checkbox_next();
+                Ok(())
+            }
+            (_, KeyCode::Char('K')) if self.active_field == 4 => {
+                self.checkbox_previous();
+                Ok(())
+            }
+            (_, KeyCode::Char(' ')) if self.active_field == 4 => {
+                self.toggle_selected_checkbox()?;
+                Ok(())
+            }
             (_, KeyCode::Down | KeyCode::Char('j')) if self.active_field == 4 => {
                 self.scroll_position.0 = self.scroll_position.0.saturating_add(1);
                 Ok(())
@@ -332,36 +1026,60 @@ impl App {
                 Ok(())
             }
             (_, KeyCode::Enter) if self.active_field == 3 => {
-                self.open_editor = true;
-                self.running = false;
+                match self.selected_tino_files_row() {
+                    Some(TinoFilesRow::Header(..)) => self.toggle_selected_group(),
+                    Some(TinoFilesRow::File(_)) => {
+                        self.open_editor = true;
+                    }
+                    None => {}
+                }
                 Ok(())
             }
-            (_, KeyCode::Enter) if self.active_field == 0 => match self.selected_type() {
-                Some("Todos") => {
-                    self.create_tino_file(self.config_file.tino_dirs.todos_dir.clone().as_str())
-                }
-                Some("Ideas") => {
-                    self.create_tino_file(self.config_file.tino_dirs.ideas_dir.clone().as_str())
-                }
-                Some("Notes") => {
-                    self.create_tino_file(self.config_file.tino_dirs.notes_dir.clone().as_str())
+            (_, KeyCode::Char('s')) if self.active_field == 3 => {
+                self.config_file.list.sort = self.config_file.list.sort.next();
+                self.update_tino_files_rows();
+                self.config_file.save_list_options()?;
+                Ok(())
+            }
+            (_, KeyCode::Char('g')) if self.active_field == 3 => {
+                self.config_file.list.group = !self.config_file.list.group;
+                self.update_tino_files_rows();
+                self.config_file.save_list_options()?;
+                Ok(())
+            }
+            (_, KeyCode::Enter) if self.active_field == 0 => match self.selected_tino_type() {
+                Some(tino_type) => {
+                    let tino_dir = tino_type.dir.clone();
+                    let tino_file = self.create_tino_file(tino_dir.as_str())?;
+                    let mut message = format!("Created {}", tino_file.path.display());
+                    for note in tino_file.notes {
+                        message.push(' ');
+                        message.push_str(&note);
+                    }
+                    self.report_info(message);
+                    Ok(())
                 }
-                Some("Academic notes") => self.create_tino_file(
-                    self.config_file
-                        .tino_dirs
-                        .academic_notes_dir
-                        .clone()
-                        .as_str(),
-                ),
                 //NOTE:
-                // This should be like this because these aren't valid options for a file type,
-                // code can be added to handle this cases but not creation of files (for now).
+                // This should be like this because this isn't a valid option for a file type,
+                // code can be added to handle this case but not creation of files (for now).
                 None => Ok(()),
-                _ => Ok(()),
             },
-            (_, KeyCode::Char('v')) if self.active_field == 3 => {
-                self.file_to_preview = self.get_file_content()?;
-                self.scroll_position = (0, 0);
+            (_, KeyCode::Char('v'))
+                if self.active_field == 3 && self.selected_tino_file().is_some() =>
+            {
+                self.preview_selected_tino_file()?;
+                Ok(())
+            }
+            (_, KeyCode::Char('t')) if self.active_field == 3 => {
+                self.todo_filter = self.todo_filter.next();
+                self.update_tino_files_rows();
+                self.request_preview();
+                Ok(())
+            }
+            (_, KeyCode::Char('a')) if self.active_field == 3 => {
+                self.config_file.preview.auto = !self.config_file.preview.auto;
+                self.request_preview();
+                self.config_file.save_preview_options()?;
                 Ok(())
             }
             _ => {
@@ -373,3 +1091,10 @@ impl App {
         }
     }
 }
+
+/// Highlights a line of the preview, like the selected checkbox.
+fn highlight_preview_line(text: &mut Text, line: usize) {
+    if let Some(line) = text.lines.get_mut(line) {
+        *line = std::mem::take(line).patch_style(Style::default().bg(Color::DarkGray));
+    }
+}
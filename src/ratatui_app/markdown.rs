@@ -0,0 +1,410 @@
+use pulldown_cmark::{
+    CodeBlockKind, Event, HeadingLevel, MetadataBlockKind, Options, Parser, Tag, TagEnd,
+};
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+    widgets::{Paragraph, Wrap},
+};
+
+use crate::ratatui_app::highlight::highlight_code;
+
+/// Markdown rendered for the file preview.
+#[derive(Debug, Clone)]
+pub struct RenderedMarkdown {
+    pub text: Text<'static>,
+    /// Line of the source, starting at 0, where each rendered line comes from.
+    pub source_lines: Vec<usize>,
+}
+
+impl RenderedMarkdown {
+    /// `content` as it is, for the raw preview.
+    pub fn raw(content: &str) -> Self {
+        let text = Text::from(content.to_string());
+        let source_lines = (0..text.lines.len()).collect();
+        Self { text, source_lines }
+    }
+
+    /// First rendered line that comes from `source_line` or a line after it.
+    pub fn rendered_line(&self, source_line: usize) -> usize {
+        self.source_lines
+            .iter()
+            .position(|line| *line >= source_line)
+            .unwrap_or(self.source_lines.len().saturating_sub(1))
+    }
+}
+
+/// Row where the line `line` of `text` starts when it's wrapped to `width` columns,
+/// like a [`Paragraph`] with [`Wrap`] shows it.
+///
+/// Without a width, before the preview is rendered the first time, it's `line`.
+pub fn wrapped_row(text: &Text, line: usize, width: u16, trim: bool) -> usize {
+    if width == 0 {
+        return line;
+    }
+    text.lines
+        .iter()
+        .take(line)
+        .map(|line| {
+            Paragraph::new(line.clone())
+                .wrap(Wrap { trim })
+                .line_count(width)
+        })
+        .sum()
+}
+
+/// A list being rendered, `number` is the number of the next item of ordered lists.
+struct List {
+    number: Option<u64>,
+    indent: usize,
+}
+
+struct Renderer {
+    width: usize,
+    lines: Vec<Line<'static>>,
+    source_lines: Vec<usize>,
+    spans: Vec<Span<'static>>,
+    source_line: usize,
+    styles: Vec<Style>,
+    lists: Vec<List>,
+    item_marker: Option<Span<'static>>,
+    block_quotes: usize,
+    code_block: Option<(String, String)>,
+    links: Vec<String>,
+}
+
+/// Renders markdown `content` as styled text, rules are `width` characters long.
+pub fn render_markdown(content: &str, width: usize) -> RenderedMarkdown {
+    let mut renderer = Renderer {
+        width,
+        lines: vec![],
+        source_lines: vec![],
+        spans: vec![],
+        source_line: 0,
+        styles: vec![Style::default()],
+        lists: vec![],
+        item_marker: None,
+        block_quotes: 0,
+        code_block: None,
+        links: vec![],
+    };
+
+    let options = Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_YAML_STYLE_METADATA_BLOCKS
+        | Options::ENABLE_PLUSES_DELIMITED_METADATA_BLOCKS;
+    // NOTE: The line of an offset is the number of line breaks before it.
+    let line_breaks: Vec<usize> = content.match_indices('\n').map(|(i, _)| i).collect();
+    for (event, range) in Parser::new_ext(content, options).into_offset_iter() {
+        if renderer.spans.is_empty() && renderer.code_block.is_none() {
+            renderer.source_line = line_breaks.partition_point(|&i| i < range.start);
+        }
+        renderer.event(event);
+    }
+    renderer.flush_line();
+    while renderer
+        .lines
+        .last()
+        .is_some_and(|line| line.spans.is_empty())
+    {
+        renderer.lines.pop();
+        renderer.source_lines.pop();
+    }
+
+    RenderedMarkdown {
+        text: Text::from(renderer.lines),
+        source_lines: renderer.source_lines,
+    }
+}
+
+impl Renderer {
+    fn event(&mut self, event: Event) {
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) => match &mut self.code_block {
+                Some((_, code)) => code.push_str(&text),
+                None => self.push_span(text.to_string(), Style::default()),
+            },
+            Event::Code(code) => {
+                self.push_span(code.to_string(), Style::default().fg(Color::Yellow));
+            }
+            Event::Html(html) | Event::InlineHtml(html) => {
+                self.push_span(
+                    html.trim_end().to_string(),
+                    Style::default().fg(Color::DarkGray),
+                );
+            }
+            Event::SoftBreak => self.push_span(String::from(" "), Style::default()),
+            Event::HardBreak => self.flush_line(),
+            Event::Rule => {
+                self.flush_line();
+                self.spans.push(Span::styled(
+                    "─".repeat(self.width.max(3)),
+                    Style::default().fg(Color::DarkGray),
+                ));
+                self.end_block();
+            }
+            Event::TaskListMarker(checked) => {
+                let (checkbox, color) = if checked {
+                    ("[x] ", Color::Green)
+                } else {
+                    ("[ ] ", Color::White)
+                };
+                self.push_span(String::from(checkbox), Style::default().fg(color));
+            }
+            _ => {}
+        }
+    }
+
+    fn start(&mut self, tag: Tag) {
+        match tag {
+            Tag::Heading { level, .. } => {
+                self.flush_line();
+                let style = match level {
+                    HeadingLevel::H1 => Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+                    HeadingLevel::H2 => Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                    _ => Style::default().add_modifier(Modifier::BOLD),
+                };
+                self.push_style(style);
+            }
+            Tag::BlockQuote(_) => {
+                self.flush_line();
+                self.block_quotes += 1;
+                self.push_style(Style::default().fg(Color::Gray));
+            }
+            Tag::CodeBlock(kind) => {
+                self.flush_line();
+                let language = match kind {
+                    CodeBlockKind::Fenced(info) => {
+                        // NOTE: The code starts after the line of the fence.
+                        self.source_line += 1;
+                        info.split_whitespace().next().unwrap_or("").to_string()
+                    }
+                    CodeBlockKind::Indented => String::new(),
+                };
+                self.code_block = Some((language, String::new()));
+            }
+            Tag::MetadataBlock(kind) => {
+                self.flush_line();
+                // NOTE: Frontmatter is shown like a code block, it starts after the delimiter.
+                self.source_line += 1;
+                let language = match kind {
+                    MetadataBlockKind::YamlStyle => "yaml",
+                    MetadataBlockKind::PlusesStyle => "toml",
+                };
+                self.code_block = Some((language.to_string(), String::new()));
+            }
+            Tag::List(number) => {
+                self.flush_line();
+                self.lists.push(List { number, indent: 2 });
+            }
+            Tag::Item => {
+                self.flush_line();
+                if let Some(list) = self.lists.last_mut() {
+                    let marker = match list.number.as_mut() {
+                        Some(number) => {
+                            *number += 1;
+                            format!("{}. ", *number - 1)
+                        }
+                        None => String::from("• "),
+                    };
+                    list.indent = marker.chars().count();
+                    self.item_marker =
+                        Some(Span::styled(marker, Style::default().fg(Color::Magenta)));
+                }
+            }
+            Tag::Emphasis => self.push_style(Style::default().add_modifier(Modifier::ITALIC)),
+            Tag::Strong => self.push_style(Style::default().add_modifier(Modifier::BOLD)),
+            Tag::Strikethrough => {
+                self.push_style(Style::default().add_modifier(Modifier::CROSSED_OUT));
+            }
+            Tag::Link { dest_url, .. } | Tag::Image { dest_url, .. } => {
+                self.links.push(dest_url.to_string());
+                self.push_style(
+                    Style::default()
+                        .fg(Color::Blue)
+                        .add_modifier(Modifier::UNDERLINED),
+                );
+            }
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::Paragraph => {
+                self.flush_line();
+                // NOTE: Items of tight lists aren't paragraphs, so they don't get blank lines.
+                self.end_block();
+            }
+            TagEnd::Heading(_) => {
+                self.styles.pop();
+                self.flush_line();
+                self.end_block();
+            }
+            TagEnd::BlockQuote(_) => {
+                self.flush_line();
+                self.block_quotes -= 1;
+                self.styles.pop();
+                self.end_block();
+            }
+            TagEnd::CodeBlock | TagEnd::MetadataBlock(_) => {
+                if let Some((language, code)) = self.code_block.take() {
+                    for line in code_block_lines(&language, &code) {
+                        self.spans = line.spans;
+                        self.flush_line();
+                        self.source_line += 1;
+                    }
+                }
+                self.end_block();
+            }
+            TagEnd::List(_) => {
+                self.flush_line();
+                self.lists.pop();
+                if self.lists.is_empty() {
+                    self.end_block();
+                }
+            }
+            TagEnd::Item => self.flush_line(),
+            TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough => {
+                self.styles.pop();
+            }
+            TagEnd::Link | TagEnd::Image => {
+                self.styles.pop();
+                if let Some(url) = self.links.pop() {
+                    let text: String = self
+                        .spans
+                        .iter()
+                        .map(|span| span.content.as_ref())
+                        .collect();
+                    if !text.ends_with(&url) {
+                        self.push_span(format!(" ({})", url), Style::default().fg(Color::DarkGray));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn push_style(&mut self, style: Style) {
+        let current = self.styles.last().copied().unwrap_or_default();
+        self.styles.push(current.patch(style));
+    }
+
+    fn push_span(&mut self, text: String, style: Style) {
+        let current = self.styles.last().copied().unwrap_or_default();
+        self.spans.push(Span::styled(text, current.patch(style)));
+    }
+
+    /// Ends the current line, adding the block quote and list prefixes.
+    fn flush_line(&mut self) {
+        if self.spans.is_empty() && self.item_marker.is_none() {
+            return;
+        }
+
+        let mut spans = vec![];
+        if self.block_quotes > 0 {
+            spans.push(Span::styled(
+                "│ ".repeat(self.block_quotes),
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+        if let Some((last, lists)) = self.lists.split_last() {
+            let indent: usize = lists.iter().map(|list| list.indent).sum();
+            if indent > 0 {
+                spans.push(Span::from(" ".repeat(indent)));
+            }
+            spans.push(match self.item_marker.take() {
+                Some(marker) => marker,
+                None => Span::from(" ".repeat(last.indent)),
+            });
+        }
+        spans.append(&mut self.spans);
+
+        self.lines.push(Line::from(spans));
+        self.source_lines.push(self.source_line);
+    }
+
+    /// Separates blocks with a blank line, except inside lists.
+    fn end_block(&mut self) {
+        self.flush_line();
+        if !self.lists.is_empty() || self.lines.last().is_none_or(|line| line.spans.is_empty()) {
+            return;
+        }
+        self.lines.push(Line::default());
+        self.source_lines.push(self.source_line);
+    }
+}
+
+/// Lines of a code block, highlighted if the language is known.
+fn code_block_lines(language: &str, code: &str) -> Vec<Line<'static>> {
+    // NOTE: Code of unknown languages isn't highlighted.
+    highlight_code(language, code).unwrap_or_else(|| {
+        code.lines()
+            .map(|line| {
+                Line::from(Span::styled(
+                    line.to_string(),
+                    Style::default().fg(Color::Yellow),
+                ))
+            })
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rendered_lines(content: &str) -> Vec<String> {
+        render_markdown(content, 10)
+            .text
+            .lines
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn highlighted_code_blocks_keep_blank_lines() {
+        let code = "fn a() {}\n\nfn b() {}";
+        let highlighted = rendered_lines(&format!("```rust\n{code}\n```\n"));
+        let plain = rendered_lines(&format!("```unknown\n{code}\n```\n"));
+
+        assert_eq!(highlighted, vec!["fn a() {}", "", "fn b() {}"]);
+        assert_eq!(highlighted, plain);
+    }
+
+    #[test]
+    fn frontmatter_keeps_blank_lines() {
+        let lines = rendered_lines("---\ntitle: a\n\ntags: []\n---\n\n# A\n");
+
+        assert_eq!(lines, vec!["title: a", "", "tags: []", "", "A"]);
+    }
+
+    #[test]
+    fn rendered_line_points_to_the_source_line() {
+        let rendered_markdown = render_markdown("# A\n\ntext\n\n```rust\nlet a = 1;\n```\n", 10);
+
+        assert_eq!(rendered_markdown.rendered_line(2), 2);
+        assert_eq!(rendered_markdown.rendered_line(5), 4);
+    }
+
+    #[test]
+    fn wrapped_row_counts_the_rows_of_wrapped_lines() {
+        let text = Text::from("aaa bbb ccc\n\nddd");
+
+        assert_eq!(wrapped_row(&text, 2, 4, false), 4);
+        assert_eq!(wrapped_row(&text, 2, 20, false), 2);
+        assert_eq!(wrapped_row(&text, 2, 0, false), 2);
+    }
+}
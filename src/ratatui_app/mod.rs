@@ -1,3 +1,6 @@
+pub mod events;
 pub mod helper_methods;
+pub mod highlight;
 pub mod implementations;
+pub mod markdown;
 pub mod types;
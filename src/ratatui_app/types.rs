@@ -1,19 +1,75 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    path::PathBuf,
+    time::Instant,
+};
+
+use chrono::{DateTime, Local};
+
+use notify::RecommendedWatcher;
 use ratatui::widgets::ListState;
 use tui_input::Input;
 
-use crate::app::config_file::ConfigFile;
+use crate::{
+    app::{
+        agenda::{AgendaGroup, AgendaItem},
+        checklist::TodoFilter,
+        config_file::{Category, ConfigFile},
+        search::{SearchMatch, SearchMode},
+        search_index::SearchIndex,
+        tino_file::TinoFile,
+    },
+    ratatui_app::{events::Events, markdown::RenderedMarkdown},
+};
+
+/// A row of the TINO files list.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TinoFilesRow {
+    /// Header of a group, with the number of files in it.
+    Header(String, usize),
+    /// Index of the file in `tino_files`.
+    File(usize),
+}
+
+/// A row of the agenda.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AgendaRow {
+    /// Header of a group, with the number of items in it.
+    Header(AgendaGroup, usize),
+    /// Index of the item in `agenda_items`.
+    Item(usize),
+}
+
+/// A file made by `create_tino_file`.
+#[derive(Debug, Clone)]
+pub struct CreatedTinoFile {
+    pub path: PathBuf,
+    /// Why the name isn't the one the user asked for.
+    pub notes: Vec<String>,
+}
+
+/// The previewed file as it was last rendered, with what it was rendered from.
+#[derive(Debug)]
+pub struct RenderedPreview {
+    pub content: String,
+    pub width: u16,
+    pub raw: bool,
+    pub rendered_markdown: RenderedMarkdown,
+}
 
-#[derive(Clone, Copy)]
-pub enum TinoFileTypes {
-    Todo,
-    Idea,
-    Note,
-    AcademicNote,
+/// Message shown in the status bar.
+#[derive(Debug, Clone)]
+pub struct StatusMessage {
+    pub text: String,
+    pub is_error: bool,
+    pub shown_at: Instant,
 }
 
 #[derive(Debug)]
 pub struct App {
     pub running: bool,
+    /// `true` when something shown changed since the last frame.
+    pub needs_redraw: bool,
     pub active_field: usize,
     pub open_editor: bool,
     pub config_file: ConfigFile,
@@ -21,9 +77,63 @@ pub struct App {
     pub file_name_input: Input,
     pub type_items: Vec<String>,
     pub type_state: ListState,
-    pub category_items: Vec<String>,
+    pub category_items: Vec<Category>,
     pub category_state: ListState,
-    pub tino_files: Vec<(String, String)>,
+    pub tino_files: Vec<TinoFile>,
+    pub tino_files_rows: Vec<TinoFilesRow>,
+    pub collapsed_groups: HashSet<String>,
     pub tino_files_state: ListState,
+    /// `true` while the user types in the filter of the TINO files list.
+    pub filtering: bool,
+    pub filter_input: Input,
+    /// Indices of the title characters matched by the filter, by index in `tino_files`.
+    pub filter_matches: HashMap<usize, Vec<usize>>,
+    pub todo_filter: TodoFilter,
+    /// `true` while the user types in the search input.
+    pub searching: bool,
+    pub search_input: Input,
+    pub search_mode: SearchMode,
+    /// `true` when the search results are shown instead of the TINO files list.
+    pub show_search_results: bool,
+    pub search_results: Vec<SearchMatch>,
+    pub search_state: ListState,
+    /// Loaded with the first search.
+    pub search_index: Option<SearchIndex>,
+    /// `true` when the agenda is shown instead of the TINO files list.
+    pub show_agenda: bool,
+    pub agenda_items: Vec<AgendaItem>,
+    pub agenda_rows: Vec<AgendaRow>,
+    pub agenda_state: ListState,
     pub file_to_preview: String,
+    /// Path of the file shown in the preview.
+    pub previewed_file: Option<String>,
+    /// `true` to preview the markdown source instead of rendering it.
+    pub raw_preview: bool,
+    /// Index of the selected checkbox of the previewed todo file.
+    pub checkbox_cursor: Option<usize>,
+    /// Height of the preview text, set when it's rendered.
+    pub preview_height: u16,
+    /// Width of the preview text, set when it's rendered.
+    pub preview_width: u16,
+    /// Kept between frames, see `Helpers::rendered_preview`.
+    pub rendered_preview: Option<RenderedPreview>,
+    /// Scroll position of the files previewed before, by path.
+    pub preview_scroll_positions: HashMap<String, (u16, u16)>,
+    /// When the selected TINO file was asked to be previewed, see `Helpers::request_preview`.
+    pub preview_requested_at: Option<Instant>,
+    pub events: Events,
+    /// Kept alive to receive changes of the TINO directories.
+    pub watcher: Option<RecommendedWatcher>,
+    /// When the TINO directories last changed, if the TINO files weren't reloaded since.
+    pub fs_changed_at: Option<Instant>,
+    /// Incremented by every reload of the TINO files, so older results are dropped.
+    pub tino_files_generation: u64,
+    /// `true` while a search runs in the background.
+    pub search_running: bool,
+    /// Query submitted while a search was running, it's run when the search is done.
+    pub queued_search: Option<String>,
+    pub status_message: Option<StatusMessage>,
+    /// Recent errors, newest first.
+    pub error_log: VecDeque<(DateTime<Local>, String)>,
+    pub show_error_log: bool,
 }